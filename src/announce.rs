@@ -0,0 +1,130 @@
+use std::env;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Something that can speak a short piece of text out loud.
+pub trait Announcer: Send + Sync {
+    /// The command that speaks `text`.
+    fn command(&self, text: &str) -> Command;
+
+    /// Speak `text` in the background, ignoring any failure.
+    fn announce(&self, text: &str) {
+        let mut command = tokio::process::Command::from(self.command(text));
+        command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+        let _ = command.spawn();
+    }
+}
+
+/// macOS `say`.
+pub struct Say;
+
+impl Announcer for Say {
+    fn command(&self, text: &str) -> Command {
+        let mut command = Command::new("say");
+        command.arg(text);
+        command
+    }
+}
+
+/// eSpeak, either the original `espeak` or `espeak-ng`.
+pub struct Espeak {
+    pub program: &'static str,
+}
+
+impl Announcer for Espeak {
+    fn command(&self, text: &str) -> Command {
+        let mut command = Command::new(self.program);
+        command.arg(text);
+        command
+    }
+}
+
+/// speech-dispatcher's `spd-say`.
+pub struct SpdSay;
+
+impl Announcer for SpdSay {
+    fn command(&self, text: &str) -> Command {
+        let mut command = Command::new("spd-say");
+        // Without --wait spd-say returns before speaking, so announcements could overlap
+        command.arg("--wait").arg(text);
+        command
+    }
+}
+
+/// A user supplied command line. Every `{}` is replaced with the text, which
+/// is otherwise passed as the last argument. The template is split on
+/// whitespace and run directly, without a shell.
+pub struct CustomCommand {
+    template: Vec<String>,
+}
+
+impl CustomCommand {
+    pub fn new(template: &str) -> Option<Self> {
+        let template: Vec<String> = template.split_whitespace().map(str::to_string).collect();
+        if template.is_empty() {
+            None
+        } else {
+            Some(Self { template })
+        }
+    }
+}
+
+impl Announcer for CustomCommand {
+    fn command(&self, text: &str) -> Command {
+        let mut command = Command::new(&self.template[0]);
+        let args = &self.template[1..];
+        if self.template.iter().any(|arg| arg.contains("{}")) {
+            command.args(args.iter().map(|arg| arg.replace("{}", text)));
+        } else {
+            command.args(args).arg(text);
+        }
+        command
+    }
+}
+
+/// Programs tried in order when no voice command is given.
+const KNOWN_PROGRAMS: &[&str] = &["say", "espeak-ng", "espeak", "spd-say"];
+
+fn known(program: &str) -> Option<Box<dyn Announcer>> {
+    match program {
+        "say" => Some(Box::new(Say)),
+        "espeak-ng" => Some(Box::new(Espeak { program: "espeak-ng" })),
+        "espeak" => Some(Box::new(Espeak { program: "espeak" })),
+        "spd-say" => Some(Box::new(SpdSay)),
+        _ => None,
+    }
+}
+
+/// The first known speech program found on `PATH`.
+pub fn detect() -> Option<Box<dyn Announcer>> {
+    KNOWN_PROGRAMS
+        .iter()
+        .find(|program| find_on_path(program))
+        .and_then(|program| known(program))
+}
+
+/// The announcer for `--voice-cmd`: one of the known program names, or
+/// otherwise a custom command template.
+pub fn from_voice_cmd(voice_cmd: &str) -> Option<Box<dyn Announcer>> {
+    known(voice_cmd.trim()).or_else(|| {
+        CustomCommand::new(voice_cmd).map(|custom| Box::new(custom) as Box<dyn Announcer>)
+    })
+}
+
+pub fn find_on_path(program: &str) -> bool {
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|dir| is_executable(&dir.join(program)))
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file()
+}
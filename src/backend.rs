@@ -0,0 +1,213 @@
+use crate::frame::{CellStyle, Frame, Run, CONTINUATION};
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, BeginSynchronizedUpdate, Clear, ClearType, EndSynchronizedUpdate},
+};
+use std::io::{self, Stdout, Write};
+
+/// Where rendered frames end up. The layout code in `ui` only ever talks to
+/// this trait, so the same screens can drive the terminal, tests and plain
+/// text output.
+pub trait RenderBackend {
+    /// Size of the drawing area as `(width, height)`.
+    fn size(&self) -> (u16, u16);
+
+    /// Blank the whole drawing area.
+    fn clear(&mut self) -> io::Result<()>;
+
+    /// Draw runs of cells at their positions.
+    fn draw(&mut self, runs: &[Run]) -> io::Result<()>;
+
+    /// Make everything drawn so far visible.
+    fn flush(&mut self) -> io::Result<()>;
+
+    /// Send a raw escape sequence such as the bell straight to the terminal.
+    /// Backends that are not terminals ignore it.
+    fn write_escape(&mut self, _sequence: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Draws to the terminal on stdout, wrapping each frame in a synchronized
+/// update so it appears at once.
+pub struct CrosstermBackend {
+    out: Stdout,
+    in_update: bool,
+    /// The lines drawn into in inline mode, or `None` for the whole screen.
+    band: Option<Band>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Band {
+    top: u16,
+    height: u16,
+}
+
+impl CrosstermBackend {
+    pub fn new() -> Self {
+        Self { out: io::stdout(), in_update: false, band: None }
+    }
+
+    /// Draw into `height` lines starting at the cursor, scrolling the
+    /// terminal first if they would run past the bottom. Needs raw mode to
+    /// find out where the cursor ended up.
+    pub fn inline(height: u16) -> io::Result<Self> {
+        let rows = terminal::size().map_or(height, |(_, rows)| rows);
+        let height = height.clamp(1, rows.max(1));
+        let mut out = io::stdout();
+        queue!(out, Print("\r\n".repeat(height as usize - 1)))?;
+        if height > 1 {
+            queue!(out, cursor::MoveUp(height - 1))?;
+        }
+        out.flush()?;
+        let (_, top) = cursor::position()?;
+        Ok(Self { out, in_update: false, band: Some(Band { top, height }) })
+    }
+
+    fn begin_update(&mut self) -> io::Result<()> {
+        if !self.in_update {
+            queue!(self.out, BeginSynchronizedUpdate)?;
+            self.in_update = true;
+        }
+        Ok(())
+    }
+}
+
+impl RenderBackend for CrosstermBackend {
+    fn size(&self) -> (u16, u16) {
+        let (width, height) = terminal::size().unwrap_or((80, 24));
+        (width, self.band.map_or(height, |band| band.height))
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.begin_update()?;
+        match self.band {
+            Some(band) => {
+                for row in 0..band.height {
+                    queue!(self.out, cursor::MoveTo(0, band.top + row), Clear(ClearType::CurrentLine))?;
+                }
+                Ok(())
+            }
+            None => queue!(self.out, Clear(ClearType::All)),
+        }
+    }
+
+    fn draw(&mut self, runs: &[Run]) -> io::Result<()> {
+        self.begin_update()?;
+        let top = self.band.map_or(0, |band| band.top);
+        let mut current = CellStyle::default();
+        for run in runs {
+            queue!(self.out, cursor::MoveTo(run.x, top + run.y))?;
+            let mut text = String::new();
+            for cell in run.cells.iter().filter(|cell| cell.ch != CONTINUATION) {
+                if cell.style != current {
+                    queue!(self.out, Print(&text))?;
+                    text.clear();
+                    queue!(self.out, SetAttribute(Attribute::Reset), ResetColor)?;
+                    if let Some(color) = cell.style.fg {
+                        queue!(self.out, SetForegroundColor(color))?;
+                    }
+                    if cell.style.dim {
+                        queue!(self.out, SetAttribute(Attribute::Dim))?;
+                    }
+                    current = cell.style;
+                }
+                text.push(cell.ch);
+            }
+            queue!(self.out, Print(&text))?;
+        }
+        queue!(self.out, SetAttribute(Attribute::Reset), ResetColor)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(band) = self.band {
+            // Park on the last line, so leaving just needs a line break
+            queue!(self.out, cursor::MoveTo(0, band.top + band.height - 1))?;
+        }
+        if self.in_update {
+            queue!(self.out, EndSynchronizedUpdate)?;
+            self.in_update = false;
+        }
+        self.out.flush()
+    }
+
+    fn write_escape(&mut self, sequence: &str) -> io::Result<()> {
+        self.out.write_all(sequence.as_bytes())?;
+        self.out.flush()
+    }
+}
+
+/// Keeps the drawn cells in memory, mostly so tests can inspect a screen.
+pub struct MemoryBackend {
+    screen: Frame,
+}
+
+impl MemoryBackend {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self { screen: Frame::new(width, height) }
+    }
+
+    pub fn screen(&self) -> &Frame {
+        &self.screen
+    }
+}
+
+impl RenderBackend for MemoryBackend {
+    fn size(&self) -> (u16, u16) {
+        (self.screen.width(), self.screen.height())
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.screen = Frame::new(self.screen.width(), self.screen.height());
+        Ok(())
+    }
+
+    fn draw(&mut self, runs: &[Run]) -> io::Result<()> {
+        for run in runs {
+            for (offset, cell) in run.cells.iter().enumerate() {
+                self.screen.put(run.x as usize + offset, run.y as usize, cell.ch, cell.style);
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Prints every flushed frame as plain text without any escape sequences,
+/// for outputs that are not terminals.
+pub struct TextBackend<W: Write> {
+    screen: MemoryBackend,
+    out: W,
+}
+
+impl<W: Write> TextBackend<W> {
+    pub fn new(out: W, width: u16, height: u16) -> Self {
+        Self { screen: MemoryBackend::new(width, height), out }
+    }
+}
+
+impl<W: Write> RenderBackend for TextBackend<W> {
+    fn size(&self) -> (u16, u16) {
+        self.screen.size()
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        self.screen.clear()
+    }
+
+    fn draw(&mut self, runs: &[Run]) -> io::Result<()> {
+        self.screen.draw(runs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for line in self.screen.screen().text_lines() {
+            writeln!(self.out, "{}", line)?;
+        }
+        writeln!(self.out)?;
+        self.out.flush()
+    }
+}
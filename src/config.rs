@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Settings from the config file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Key bindings on top of the defaults, such as `q = "quit"`.
+    pub keys: BTreeMap<String, String>,
+}
+
+/// Where the config file is looked for: `$XDG_CONFIG_HOME/countdown-tui/config.toml`,
+/// or under `~/.config` when that is not set.
+pub fn config_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("countdown-tui").join("config.toml"))
+}
+
+impl Config {
+    pub fn parse(toml: &str) -> Result<Self, String> {
+        toml::from_str(toml).map_err(|e| e.message().to_string())
+    }
+
+    /// Read `path`, or the default config file when it is `None`. Only the
+    /// default file may be missing.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match config_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+        let toml = match std::fs::read_to_string(&path) {
+            Ok(toml) => toml,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !required => return Ok(Self::default()),
+            Err(e) => return Err(format!("error: cannot read {}: {}", path.display(), e)),
+        };
+        Self::parse(&toml).map_err(|e| format!("error: invalid config {}: {}", path.display(), e.trim_end()))
+    }
+}
@@ -0,0 +1,217 @@
+use crate::summary::EndReason;
+use crate::time_parser::parse_duration_string;
+use crate::timer::{CountdownState, TimerMode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, watch};
+
+/// How much time `SIGUSR2` and the add and subtract keys change.
+pub const ADD_TIME_STEP: Duration = Duration::from_secs(60);
+
+/// A request to change a running countdown that does not come from the
+/// keyboard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Control {
+    TogglePause,
+    Pause,
+    Resume,
+    AddTime(Duration),
+    SubtractTime(Duration),
+    /// Start over from the full duration.
+    Reset,
+    Lap,
+    /// Replace the title; an empty one removes it.
+    SetTitle(String),
+    /// Change nothing, just report the status.
+    Status,
+    Stop(EndReason),
+}
+
+/// A control on its way to the countdown, with somewhere to send the status
+/// once it has been applied.
+pub struct ControlRequest {
+    pub control: Control,
+    pub reply: Option<oneshot::Sender<Status>>,
+}
+
+impl ControlRequest {
+    pub fn new(control: Control) -> Self {
+        Self { control, reply: None }
+    }
+}
+
+/// The countdown's end of the control interfaces: requests come in on
+/// `requests`, and every change of status goes out on `status`.
+pub struct Remote {
+    pub requests: UnboundedReceiver<ControlRequest>,
+    pub status: watch::Sender<Option<Status>>,
+}
+
+impl Remote {
+    /// Let watchers know about `status` if it differs from the last one.
+    pub fn publish(&self, status: Status) {
+        self.status.send_if_modified(|current| {
+            let changed = current.as_ref() != Some(&status);
+            if changed {
+                *current = Some(status);
+            }
+            changed
+        });
+    }
+}
+
+/// The requests of the control protocol, one JSON object per line such as
+/// `{"command":"add","duration":"5m"}`. They double as the `ctl` subcommands.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, clap::Subcommand)]
+#[serde(tag = "command", rename_all = "lowercase")]
+pub enum Request {
+    /// Pause the countdown
+    Pause,
+    /// Resume a paused countdown
+    Resume,
+    /// Add time to the countdown
+    Add {
+        #[arg(value_name = "DURATION", help = "How much to add, e.g. 5m")]
+        duration: String,
+    },
+    /// Print the status of the countdown as JSON
+    Status,
+    /// Cancel the countdown
+    Stop,
+    /// Record a lap
+    Lap,
+    /// Change the title, or remove it when empty
+    Title {
+        #[arg(value_name = "TEXT")]
+        text: String,
+    },
+}
+
+impl Request {
+    pub fn into_control(self) -> Result<Control, String> {
+        Ok(match self {
+            Request::Pause => Control::Pause,
+            Request::Resume => Control::Resume,
+            Request::Add { duration } => Control::AddTime(
+                parse_duration_string(&duration).map_err(|e| format!("invalid duration '{}': {}", duration, e))?,
+            ),
+            Request::Status => Control::Status,
+            Request::Stop => Control::Stop(EndReason::Cancelled),
+            Request::Lap => Control::Lap,
+            Request::Title { text } => Control::SetTitle(text),
+        })
+    }
+}
+
+/// A snapshot of the countdown for the control interfaces. Durations are
+/// whole seconds, like in the `--summary` output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    /// `running` or `paused`, or the end reason once it is over.
+    pub state: String,
+    pub mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub total: u64,
+    pub elapsed: u64,
+    pub remaining: u64,
+    pub paused: u64,
+    pub pause_count: u32,
+    pub laps: Vec<u64>,
+}
+
+impl Status {
+    pub fn new(state: &CountdownState, title: Option<&str>, ended: Option<EndReason>) -> Self {
+        Self {
+            state: match ended {
+                Some(reason) => reason.as_str(),
+                None if state.is_paused() => "paused",
+                None => "running",
+            }
+            .to_string(),
+            mode: match state.mode() {
+                TimerMode::CountDown => "down",
+                TimerMode::CountUp => "up",
+            }
+            .to_string(),
+            title: title.map(str::to_string),
+            total: state.total_duration().as_secs(),
+            elapsed: state.elapsed().as_secs(),
+            remaining: state.time_left().as_secs(),
+            paused: state.paused_total().as_secs(),
+            pause_count: state.pause_count(),
+            laps: state.laps().iter().map(Duration::as_secs).collect(),
+        }
+    }
+}
+
+/// Reply to one protocol request: the status after applying it, or why it
+/// could not be applied.
+#[derive(Clone, Debug, Serialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Response {
+    pub fn from_result(result: Result<Status, String>) -> Self {
+        match result {
+            Ok(status) => Self { ok: true, status: Some(status), error: None },
+            Err(error) => Self { ok: false, status: None, error: Some(error) },
+        }
+    }
+}
+
+/// Hand `control` to the countdown and wait for the status after it ran.
+pub async fn dispatch(tx: &UnboundedSender<ControlRequest>, control: Control) -> Result<Status, String> {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    tx.send(ControlRequest { control, reply: Some(reply_tx) })
+        .map_err(|_| "the countdown has ended".to_string())?;
+    reply_rx.await.map_err(|_| "the countdown has ended".to_string())
+}
+
+/// Turn signals into controls: SIGTERM, SIGHUP and SIGINT cancel the
+/// countdown, SIGUSR1 toggles pause and SIGUSR2 adds `ADD_TIME_STEP`.
+#[cfg(unix)]
+pub fn forward_signals(tx: UnboundedSender<ControlRequest>) -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let handlers = [
+        (SignalKind::terminate(), None),
+        (SignalKind::hangup(), None),
+        (SignalKind::interrupt(), None),
+        (SignalKind::user_defined1(), Some(Control::TogglePause)),
+        (SignalKind::user_defined2(), Some(Control::AddTime(ADD_TIME_STEP))),
+    ];
+
+    for (kind, control) in handlers {
+        let control = control.unwrap_or(Control::Stop(EndReason::Signal(kind.as_raw_value())));
+        let mut stream = signal(kind)?;
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            while stream.recv().await.is_some() {
+                if tx.send(ControlRequest::new(control.clone())).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Only Ctrl+C can be caught here, and it cancels the countdown.
+#[cfg(not(unix))]
+pub fn forward_signals(tx: UnboundedSender<ControlRequest>) -> std::io::Result<()> {
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if tx.send(ControlRequest::new(Control::Stop(EndReason::Signal(2)))).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(())
+}
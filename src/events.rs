@@ -0,0 +1,135 @@
+use crate::summary::EndReason;
+use crate::threshold::Urgency;
+use crate::timer::CountdownState;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Where `--events` writes: stdout, or the file after `json=`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventTarget {
+    pub path: Option<PathBuf>,
+}
+
+impl EventTarget {
+    /// Parse `json` or `json=PATH`. JSON is the only format so far.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let (format, path) = match input.split_once('=') {
+            Some((format, path)) => (format, Some(PathBuf::from(path))),
+            None => (input, None),
+        };
+        if format != "json" {
+            return Err(format!("unknown event format '{}', expected json", format));
+        }
+        if path.as_ref().is_some_and(|path| path.as_os_str().is_empty()) {
+            return Err("missing path after json=".to_string());
+        }
+        Ok(Self { path })
+    }
+}
+
+/// Something that happened to the countdown.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event {
+    Started,
+    Tick,
+    Paused,
+    Resumed,
+    /// Time was added, or taken off when negative.
+    Adjusted { added: i64 },
+    /// The countdown started over.
+    Reset,
+    Lap { lap: u64 },
+    /// A `--warn` or `--critical` threshold was reached.
+    Threshold { level: &'static str },
+    Finished,
+    Cancelled {
+        reason: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        signal: Option<i32>,
+    },
+}
+
+impl Event {
+    pub fn adjusted(added: Duration) -> Self {
+        Event::Adjusted { added: added.as_secs() as i64 }
+    }
+
+    pub fn subtracted(removed: Duration) -> Self {
+        Event::Adjusted { added: -(removed.as_secs() as i64) }
+    }
+
+    pub fn lap(at: Duration) -> Self {
+        Event::Lap { lap: at.as_secs() }
+    }
+
+    pub fn threshold(urgency: Urgency) -> Self {
+        let level = match urgency {
+            Urgency::Normal => "normal",
+            Urgency::Warning => "warning",
+            Urgency::Critical => "critical",
+        };
+        Event::Threshold { level }
+    }
+
+    pub fn cancelled(reason: EndReason) -> Self {
+        Event::Cancelled {
+            reason: reason.as_str(),
+            signal: match reason {
+                EndReason::Signal(signal) => Some(signal),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// One line of the event stream: the event, when it happened and where the
+/// timer stood. Durations are whole seconds.
+#[derive(Serialize)]
+struct Record<'a> {
+    #[serde(flatten)]
+    event: &'a Event,
+    time: String,
+    total: u64,
+    elapsed: u64,
+    remaining: u64,
+}
+
+/// Writes events as newline-delimited JSON.
+pub struct EventLog {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EventLog {
+    /// Open the target, appending to the file if it already exists.
+    pub fn open(target: &EventTarget) -> io::Result<Self> {
+        let out: Box<dyn Write + Send> = match &target.path {
+            Some(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+            None => Box::new(io::stdout()),
+        };
+        Ok(Self { out: Mutex::new(out) })
+    }
+
+    /// Write `event` with the state of the timer, ignoring failures so a
+    /// closed pipe cannot stop the countdown.
+    pub fn emit(&self, state: &CountdownState, event: Event) {
+        let record = Record {
+            event: &event,
+            time: chrono::Local::now().to_rfc3339(),
+            total: state.total_duration().as_secs(),
+            elapsed: state.elapsed().as_secs(),
+            remaining: state.time_left().as_secs(),
+        };
+        let Ok(mut line) = serde_json::to_string(&record) else {
+            return;
+        };
+        line.push('\n');
+        if let Ok(mut out) = self.out.lock() {
+            let _ = out.write_all(line.as_bytes()).and_then(|_| out.flush());
+        }
+    }
+}
@@ -107,23 +107,141 @@ pub fn get_font() -> HashMap<char, CharMap> {
     font
 }
 
-pub fn get_paused_text() -> CharMap {
-    vec![
-        "█▀▄ ▄▀▄ █ █ ▄▀▀ ██▀ █▀▄",
-        "█▀  █▀█ ▀▄█ ▄██ █▄▄ █▄▀",
-    ]
+/// Glyphs of the small text font used for titles and banners. Each glyph is
+/// four rows of pixels (`#` set, `.` clear) that are drawn two rows per line
+/// with half blocks, so text comes out two lines high.
+const TEXT_GLYPHS: &[(char, [&str; 4])] = &[
+    ('A', [".#.", "#.#", "###", "#.#"]),
+    ('B', ["##.", "###", "#.#", "###"]),
+    ('C', [".##", "#..", "#..", ".##"]),
+    ('D', ["##.", "#.#", "#.#", "##."]),
+    ('E', ["###", "##.", "#..", "###"]),
+    ('F', ["###", "#..", "##.", "#.."]),
+    ('G', [".##", "#..", "#.#", ".##"]),
+    ('H', ["#.#", "#.#", "###", "#.#"]),
+    ('I', ["###", ".#.", ".#.", "###"]),
+    ('J', ["..#", "..#", "#.#", ".#."]),
+    ('K', ["#.#", "##.", "#.#", "#.#"]),
+    ('L', ["#..", "#..", "#..", "###"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#...#"]),
+    ('N', ["#..#", "##.#", "#.##", "#..#"]),
+    ('O', [".#.", "#.#", "#.#", ".#."]),
+    ('P', ["##.", "#.#", "##.", "#.."]),
+    ('Q', [".#.", "#.#", "#.#", ".##"]),
+    ('R', ["##.", "#.#", "##.", "#.#"]),
+    ('S', [".##", "#..", ".##", "###"]),
+    ('T', ["###", ".#.", ".#.", ".#."]),
+    ('U', ["#.#", "#.#", "#.#", ".##"]),
+    ('V', ["#.#", "#.#", "#.#", ".#."]),
+    ('W', ["#...#", "#...#", "#.#.#", ".#.#."]),
+    ('X', ["#.#", ".#.", ".#.", "#.#"]),
+    ('Y', ["#.#", "#.#", ".#.", ".#."]),
+    ('Z', ["###", "..#", "#..", "###"]),
+    ('a', ["...", ".##", "#.#", ".##"]),
+    ('b', ["#..", "##.", "#.#", "##."]),
+    ('c', ["...", ".##", "#..", ".##"]),
+    ('d', ["..#", ".##", "#.#", ".##"]),
+    ('e', ["...", "###", "##.", "###"]),
+    ('f', [".##", ".#.", "###", ".#."]),
+    ('g', [".##", "#.#", ".##", "##."]),
+    ('h', ["#..", "##.", "#.#", "#.#"]),
+    ('i', ["#", ".", "#", "#"]),
+    ('j', ["..#", "...", "..#", "##."]),
+    ('k', ["#..", "#.#", "##.", "#.#"]),
+    ('l', ["#.", "#.", "#.", ".#"]),
+    ('m', [".....", "####.", "#.#.#", "#.#.#"]),
+    ('n', ["...", "##.", "#.#", "#.#"]),
+    ('o', ["...", ".#.", "#.#", ".#."]),
+    ('p', ["...", "##.", "##.", "#.."]),
+    ('q', [".##", "#.#", ".##", "..#"]),
+    ('r', ["...", "#.#", "##.", "#.."]),
+    ('s', ["...", ".##", ".#.", "##."]),
+    ('t', [".#.", "###", ".#.", ".##"]),
+    ('u', ["...", "#.#", "#.#", ".##"]),
+    ('v', ["...", "#.#", "#.#", ".#."]),
+    ('w', [".....", "#...#", "#.#.#", ".#.#."]),
+    ('x', ["...", "#.#", ".#.", "#.#"]),
+    ('y', ["...", "#.#", ".##", "##."]),
+    ('z', ["...", "##.", ".#.", ".##"]),
+    ('0', ["###", "#.#", "#.#", "###"]),
+    ('1', [".#.", "##.", ".#.", "###"]),
+    ('2', ["##.", "..#", ".#.", "###"]),
+    ('3', ["##.", ".##", "..#", "##."]),
+    ('4', ["#.#", "#.#", "###", "..#"]),
+    ('5', ["###", "##.", "..#", "##."]),
+    ('6', [".##", "#..", "###", "###"]),
+    ('7', ["###", "..#", ".#.", ".#."]),
+    ('8', ["###", ".#.", "#.#", "###"]),
+    ('9', ["###", "#.#", "###", "..#"]),
+    (' ', ["..", "..", "..", ".."]),
+    ('.', [".", ".", ".", "#"]),
+    (',', [".", ".", "#", "#"]),
+    ('!', ["#", "#", ".", "#"]),
+    ('?', ["##.", "..#", ".#.", ".#."]),
+    (':', [".", "#", ".", "#"]),
+    (';', [".#", "..", ".#", "#."]),
+    ('\'', ["#", "#", ".", "."]),
+    ('"', ["#.#", "#.#", "...", "..."]),
+    ('-', ["...", "...", "###", "..."]),
+    ('_', ["...", "...", "...", "###"]),
+    ('+', ["...", ".#.", "###", ".#."]),
+    ('=', ["...", "###", "...", "###"]),
+    ('/', ["..#", "..#", ".#.", "#.."]),
+    ('(', [".#", "#.", "#.", ".#"]),
+    (')', ["#.", ".#", ".#", "#."]),
+    ('#', ["#.#", "###", "#.#", "###"]),
+    ('%', ["#.#", "..#", ".#.", "#.#"]),
+    ('*', ["#.#", ".#.", "#.#", "..."]),
+];
+
+/// Render `text` in the small text font, or `None` if it contains a
+/// character the font has no glyph for.
+pub fn render_text(text: &str) -> Option<Vec<String>> {
+    render_text_scaled(text, 1)
 }
 
+/// Render `text` in the text font with every pixel blown up `scale` times,
+/// giving `text_height() * scale` lines.
+pub fn render_text_scaled(text: &str, scale: usize) -> Option<Vec<String>> {
+    let scale = scale.max(1);
+    let mut lines = vec![String::new(); text_height() * scale];
+    
+    for (index, ch) in text.chars().enumerate() {
+        let (_, pixels) = TEXT_GLYPHS.iter().find(|(glyph, _)| *glyph == ch)?;
+        let rows: Vec<Vec<bool>> = pixels
+            .iter()
+            .flat_map(|row| {
+                let row: Vec<bool> = row.chars().flat_map(|pixel| std::iter::repeat_n(pixel == '#', scale)).collect();
+                std::iter::repeat_n(row, scale)
+            })
+            .collect();
+        
+        for (row, line) in lines.iter_mut().enumerate() {
+            if index > 0 {
+                line.push_str(&" ".repeat(scale));
+            }
+            let top = &rows[row * 2];
+            let bottom = &rows[row * 2 + 1];
+            line.extend(top.iter().zip(bottom).map(|(top, bottom)| half_block(*top, *bottom)));
+        }
+    }
+    
+    Some(lines)
+}
 
-pub fn char_height() -> usize {
-    6
+fn half_block(top: bool, bottom: bool) -> char {
+    match (top, bottom) {
+        (true, true) => '█',
+        (true, false) => '▀',
+        (false, true) => '▄',
+        (false, false) => ' ',
+    }
 }
 
-pub fn paused_text_width() -> usize {
-    let paused = get_paused_text();
-    paused[0].chars().count()
+pub fn text_height() -> usize {
+    2
 }
 
-pub fn paused_text_height() -> usize {
-    get_paused_text().len()
-}
\ No newline at end of file
+pub fn char_height() -> usize {
+    6
+}
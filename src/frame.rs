@@ -0,0 +1,155 @@
+use crossterm::style::Color;
+use unicode_width::UnicodeWidthChar;
+
+/// Placeholder for the second column of a double-width character.
+pub const CONTINUATION: char = '\0';
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CellStyle {
+    pub fg: Option<Color>,
+    pub dim: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub style: CellStyle,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self { ch: ' ', style: CellStyle::default() }
+    }
+}
+
+/// A horizontal run of cells that changed between two frames.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Run {
+    pub x: u16,
+    pub y: u16,
+    pub cells: Vec<Cell>,
+}
+
+/// An off-screen grid of cells that is built up for every frame and then
+/// compared against the previous one, so only what changed gets written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        if x < self.width && y < self.height {
+            self.cells.get(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Set a single cell, silently dropping anything outside the frame.
+    pub fn put(&mut self, x: usize, y: usize, ch: char, style: CellStyle) {
+        if x < self.width as usize && y < self.height as usize {
+            self.cells[y * self.width as usize + x] = Cell { ch, style };
+        }
+    }
+
+    /// Write `text` from column `x`, giving double-width characters two cells
+    /// and dropping zero-width ones.
+    pub fn put_str(&mut self, x: usize, y: usize, text: &str, style: CellStyle) {
+        let mut column = x;
+        for ch in text.chars() {
+            match ch.width().unwrap_or(0) {
+                0 => {}
+                1 => {
+                    self.put(column, y, ch, style);
+                    column += 1;
+                }
+                _ => {
+                    // Never leave half of a wide character hanging off the edge
+                    if column + 1 < self.width as usize {
+                        self.put(column, y, ch, style);
+                        self.put(column + 1, y, CONTINUATION, style);
+                    }
+                    column += 2;
+                }
+            }
+        }
+    }
+
+    pub fn row(&self, y: u16) -> &[Cell] {
+        let start = y as usize * self.width as usize;
+        &self.cells[start..start + self.width as usize]
+    }
+
+    /// The frame as plain text, with trailing spaces and the blank rows
+    /// above and below the content removed.
+    pub fn text_lines(&self) -> Vec<String> {
+        let lines: Vec<String> = (0..self.height)
+            .map(|y| {
+                self.row(y)
+                    .iter()
+                    .filter(|cell| cell.ch != CONTINUATION)
+                    .map(|cell| cell.ch)
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        let first = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+        let last = lines.iter().rposition(|line| !line.is_empty()).map_or(first, |last| last + 1);
+        lines[first..last].to_vec()
+    }
+
+    /// Every row as a single run, for when there is nothing to diff against.
+    fn full(&self) -> Vec<Run> {
+        (0..self.height)
+            .map(|y| Run { x: 0, y, cells: self.row(y).to_vec() })
+            .collect()
+    }
+
+    /// Runs of cells that differ from `previous`. Frames of different sizes
+    /// cannot be compared, so the whole frame is returned instead.
+    pub fn diff(&self, previous: &Frame) -> Vec<Run> {
+        if self.width != previous.width || self.height != previous.height {
+            return self.full();
+        }
+
+        let mut runs = Vec::new();
+        for y in 0..self.height {
+            let row = self.row(y);
+            let old_row = previous.row(y);
+            let mut x = 0;
+            while x < row.len() {
+                if row[x] == old_row[x] {
+                    x += 1;
+                    continue;
+                }
+                // A changed second half means the whole wide character is redrawn
+                let start = if row[x].ch == CONTINUATION && x > 0 { x - 1 } else { x };
+                while x < row.len() && row[x] != old_row[x] {
+                    x += 1;
+                }
+                runs.push(Run { x: start as u16, y, cells: row[start..x].to_vec() });
+            }
+        }
+        runs
+    }
+}
@@ -0,0 +1,129 @@
+use crate::control::Status;
+use crate::summary::EndReason;
+use crate::timer::{CountdownState, TimerMode};
+use crate::ui::format_duration;
+use std::io::{self, Stdout, Write};
+use std::time::Duration;
+
+/// How `LinePrinter` words its lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineStyle {
+    /// `Tea: 04:59 remaining`, for logs.
+    Log,
+    /// `⏱ 04:59 Tea`, for status bars; see `status_line`.
+    Compact,
+}
+
+/// Prints the countdown as one plain status line at a time, for logs, pipes
+/// and anything else that is not a terminal.
+pub struct LinePrinter<W: Write = Stdout> {
+    out: W,
+    style: LineStyle,
+    /// Print a running countdown every this many seconds.
+    every: u64,
+    last_line: Option<String>,
+    was_paused: bool,
+}
+
+impl LinePrinter {
+    pub fn new(every: Duration, style: LineStyle) -> Self {
+        Self::with_writer(io::stdout(), every, style)
+    }
+}
+
+impl<W: Write> LinePrinter<W> {
+    pub fn with_writer(out: W, every: Duration, style: LineStyle) -> Self {
+        Self {
+            out,
+            style,
+            every: every.as_secs().max(1),
+            last_line: None,
+            was_paused: false,
+        }
+    }
+
+    pub fn writer(&self) -> &W {
+        &self.out
+    }
+
+    /// Print the time of a running countdown when it is on the interval, or
+    /// has just started or resumed.
+    pub fn running(&mut self, state: &CountdownState, title: Option<&str>) -> io::Result<()> {
+        let resumed = std::mem::take(&mut self.was_paused);
+        let due = self.last_line.is_none() || resumed || state.elapsed().as_secs() % self.every == 0;
+        if !due {
+            return Ok(());
+        }
+        if self.style == LineStyle::Compact {
+            return self.print_status(Status::new(state, title, None));
+        }
+        let suffix = match state.mode() {
+            TimerMode::CountDown => "remaining",
+            TimerMode::CountUp => "elapsed",
+        };
+        self.print(title, format!("{} {}", format_duration(state.display_duration()), suffix))
+    }
+
+    /// Print that the countdown is paused, once per pause.
+    pub fn paused(&mut self, state: &CountdownState, title: Option<&str>) -> io::Result<()> {
+        self.was_paused = true;
+        if self.style == LineStyle::Compact {
+            return self.print_status(Status::new(state, title, None));
+        }
+        self.print(title, format!("paused at {}", format_duration(state.display_duration())))
+    }
+
+    /// Print how the countdown ended.
+    pub fn ended(&mut self, state: &CountdownState, title: Option<&str>, reason: EndReason) -> io::Result<()> {
+        if self.style == LineStyle::Compact {
+            return self.print_status(Status::new(state, title, Some(reason)));
+        }
+        let at = format_duration(state.display_duration());
+        let line = match reason {
+            EndReason::Finished | EndReason::Overtime => "time's up".to_string(),
+            EndReason::Cancelled => format!("cancelled at {}", at),
+            EndReason::Signal(signal) => format!("cancelled by signal {} at {}", signal, at),
+        };
+        self.print(title, line)
+    }
+
+    fn print_status(&mut self, status: Status) -> io::Result<()> {
+        self.write_line(status_line(&status))
+    }
+
+    /// Write `text` after the title, unless it would repeat the last line.
+    fn print(&mut self, title: Option<&str>, text: String) -> io::Result<()> {
+        let line = match title {
+            Some(title) => format!("{}: {}", title, text),
+            None => text,
+        };
+        self.write_line(line)
+    }
+
+    fn write_line(&mut self, line: String) -> io::Result<()> {
+        if self.last_line.as_ref() == Some(&line) {
+            return Ok(());
+        }
+        writeln!(self.out, "{}", line)?;
+        self.out.flush()?;
+        self.last_line = Some(line);
+        Ok(())
+    }
+}
+
+/// The status as one short line for tmux, i3blocks or waybar: a symbol for
+/// the state, the time shown on screen and the title, as in `⏱ 12:34 Tea`.
+pub fn status_line(status: &Status) -> String {
+    let symbol = match status.state.as_str() {
+        "running" => "⏱",
+        "paused" => "⏸",
+        "finished" | "overtime" => "⏰",
+        _ => "⏹",
+    };
+    let secs = if status.mode == "up" { status.elapsed } else { status.remaining };
+    let time = format_duration(Duration::from_secs(secs));
+    match &status.title {
+        Some(title) => format!("{} {} {}", symbol, time, title),
+        None => format!("{} {}", symbol, time),
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+/// How many countdowns the history keeps.
+const MAX_ENTRIES: usize = 10;
+
+/// A countdown as it was started, to start it again.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// The duration or time as typed, such as `25m` or `14:15`.
+    pub duration: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// The most recently started countdowns, newest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct History {
+    pub entries: Vec<Entry>,
+}
+
+/// Where the history is kept: `$XDG_STATE_HOME/countdown-tui/history.json`,
+/// or under `~/.local/state` when that is not set.
+pub fn history_path() -> Option<PathBuf> {
+    let state_dir = env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))?;
+    Some(state_dir.join("countdown-tui").join("history.json"))
+}
+
+impl History {
+    /// Read the history, starting afresh when it is missing or unreadable.
+    pub fn load() -> Self {
+        let entries = history_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self { entries }
+    }
+
+    /// Put `entry` first, dropping an earlier copy of it and the oldest
+    /// entries beyond `MAX_ENTRIES`.
+    pub fn add(&mut self, entry: Entry) {
+        self.entries.retain(|existing| *existing != entry);
+        self.entries.insert(0, entry);
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = history_path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.entries)?)
+    }
+}
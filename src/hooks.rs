@@ -0,0 +1,129 @@
+use crate::control::{Control, ControlRequest, Status};
+use crate::summary::EndReason;
+use crate::timer::CountdownState;
+use std::io;
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// Why a hook command is being run, passed on as `COUNTDOWN_REASON`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookReason {
+    Threshold,
+    Finished,
+    Cancelled,
+}
+
+impl HookReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookReason::Threshold => "threshold",
+            HookReason::Finished => "finished",
+            HookReason::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A command to run once when this much time is left.
+#[derive(Clone, Debug)]
+pub struct ThresholdHook {
+    pub at: Duration,
+    pub command: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Hooks {
+    /// Run when the countdown ends, however it ends.
+    pub on_exit: Option<String>,
+    pub thresholds: Vec<ThresholdHook>,
+    /// Exit with the status of the `on_exit` command.
+    pub propagate_status: bool,
+}
+
+impl Hooks {
+    /// Threshold hooks passed when the time left went from `before` to `now`.
+    pub fn crossed(&self, before: Duration, now: Duration) -> impl Iterator<Item = &ThresholdHook> {
+        self.thresholds
+            .iter()
+            .filter(move |hook| before > hook.at && now <= hook.at)
+    }
+}
+
+/// Build `command` as a shell command line with the countdown described in
+/// `COUNTDOWN_*` environment variables. Durations are whole seconds.
+pub fn hook_command(command: &str, state: &CountdownState, title: Option<&str>, reason: HookReason) -> Command {
+    let mut command = shell(command);
+    command
+        .env("COUNTDOWN_REASON", reason.as_str())
+        .env("COUNTDOWN_TITLE", title.unwrap_or(""))
+        .env("COUNTDOWN_TOTAL", state.total_duration().as_secs().to_string())
+        .env("COUNTDOWN_ELAPSED", state.elapsed().as_secs().to_string())
+        .env("COUNTDOWN_REMAINING", state.time_left().as_secs().to_string());
+    command
+}
+
+/// Run the exit hook to the end while still answering `requests` with the
+/// `ended` status. A signal passes on to the hook and is returned once the
+/// hook has exited, so it can still decide the exit status; other stops
+/// change nothing, as the countdown is over.
+pub async fn run_exit_hook(
+    command: Command,
+    requests: &mut UnboundedReceiver<ControlRequest>,
+    ended: Status,
+) -> io::Result<(ExitStatus, Option<EndReason>)> {
+    let mut child = tokio::process::Command::from(command).spawn()?;
+    let mut signal = None;
+    loop {
+        tokio::select! {
+            status = child.wait() => return Ok((status?, signal)),
+            Some(ControlRequest { control, reply }) = requests.recv() => {
+                if let Some(reply) = reply {
+                    let _ = reply.send(ended.clone());
+                }
+                if let Control::Stop(reason @ EndReason::Signal(number)) = control {
+                    if signal.is_none() {
+                        signal = Some(reason);
+                        pass_on(&mut child, number);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn pass_on(child: &mut tokio::process::Child, signal: i32) {
+    if let Some(pid) = child.id() {
+        // SAFETY: kill only sends a signal to the hook we started
+        unsafe {
+            libc::kill(pid as libc::pid_t, signal);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn pass_on(child: &mut tokio::process::Child, _signal: i32) {
+    let _ = child.start_kill();
+}
+
+/// Start a threshold hook in the background with its output discarded, so
+/// it cannot scribble over the countdown.
+pub fn spawn_quietly(command: Command) {
+    let mut command = tokio::process::Command::from(command);
+    command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    let _ = command.spawn();
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
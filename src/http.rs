@@ -0,0 +1,261 @@
+use crate::control::{dispatch, ControlRequest, Request, Status};
+use serde::Deserialize;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
+
+/// Largest request body accepted, which is plenty for a duration.
+const MAX_BODY: usize = 4096;
+
+/// Largest request line and headers accepted together.
+const MAX_HEAD: usize = 8192;
+
+/// How long a client has to send its whole request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serve the HTTP API on `addr`:
+///
+/// - `GET /status` returns the status as JSON
+/// - `POST /pause` and `POST /resume` pause and resume
+/// - `POST /add` adds the duration in the body, either plain like `5m` or
+///   as `{"duration":"5m"}`
+/// - `GET /events` streams the status as Server-Sent Events on every change
+///
+/// Each request is answered and the connection closed; there is no
+/// keep-alive.
+///
+/// Requests made by web pages are refused unless they come from the page
+/// served on this address or from `allow_origin`, which alone is allowed
+/// to read the responses cross-origin. Otherwise any page open in a
+/// browser could pause the countdown or read its title. Requests for
+/// another host name, as a page sends after DNS rebinding, are refused too.
+pub async fn serve(
+    addr: SocketAddr,
+    allow_origin: Option<String>,
+    tx: UnboundedSender<ControlRequest>,
+    status_rx: watch::Receiver<Option<Status>>,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle(stream, addr, allow_origin.clone(), tx.clone(), status_rx.clone()));
+        }
+    });
+    Ok(())
+}
+
+/// Whether the `Host` header names the address we serve on. Any other name
+/// means a browser was pointed at us through DNS rebinding, so its page
+/// would count as same-origin. `localhost` is fine on a loopback address,
+/// and any address literal when serving on all interfaces.
+pub fn host_allowed(host: Option<&str>, addr: SocketAddr) -> bool {
+    // Clients that send no Host are not browsers
+    let Some(host) = host else {
+        return true;
+    };
+    let (name, port) = match host.rsplit_once(':') {
+        Some((name, port)) if !port.contains(']') => (name, port.parse().ok()),
+        _ => (host, Some(80)),
+    };
+    if port != Some(addr.port()) {
+        return false;
+    }
+    let name = name.trim_start_matches('[').trim_end_matches(']');
+    match name.parse::<IpAddr>() {
+        Ok(ip) => ip == addr.ip() || addr.ip().is_unspecified() || (ip.is_loopback() && addr.ip().is_loopback()),
+        Err(_) => name.eq_ignore_ascii_case("localhost") && (addr.ip().is_loopback() || addr.ip().is_unspecified()),
+    }
+}
+
+/// Whether a request with the `Origin` header `origin` may be answered:
+/// requests from outside a browser have none, and a page may only talk to
+/// the host it was served from, given in `host`, or to us when it is
+/// `allow_origin`.
+pub fn origin_allowed(origin: Option<&str>, host: Option<&str>, allow_origin: Option<&str>) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    let same_host = host.is_some_and(|host| origin.strip_prefix("http://").is_some_and(|origin| origin.eq_ignore_ascii_case(host)));
+    same_host || allow_origin.is_some_and(|allowed| origin.eq_ignore_ascii_case(allowed.trim_end_matches('/')))
+}
+
+/// The parts of a request the API looks at.
+struct HttpRequest {
+    method: String,
+    path: String,
+    origin: Option<String>,
+    host: Option<String>,
+    body: String,
+}
+
+/// Read a request, or the status to refuse it with when it is too large.
+async fn read_request(stream: &mut BufReader<TcpStream>) -> io::Result<Result<HttpRequest, &'static str>> {
+    let mut head = (&mut *stream).take(MAX_HEAD as u64);
+
+    let mut request_line = String::new();
+    head.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut origin = None;
+    let mut host = None;
+    loop {
+        let mut header = String::new();
+        if head.read_line(&mut header).await? == 0 {
+            if head.limit() == 0 {
+                return Ok(Err("431 Request Header Fields Too Large"));
+            }
+            break;
+        }
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let name = name.trim();
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("origin") {
+                origin = Some(value.trim().to_string());
+            } else if name.eq_ignore_ascii_case("host") {
+                host = Some(value.trim().to_string());
+            }
+        }
+    }
+    if content_length > MAX_BODY {
+        return Ok(Err("413 Payload Too Large"));
+    }
+    let mut body = vec![0; content_length];
+    stream.read_exact(&mut body).await?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+    Ok(Ok(HttpRequest { method, path, origin, host, body }))
+}
+
+async fn handle(
+    stream: TcpStream,
+    addr: SocketAddr,
+    allow_origin: Option<String>,
+    tx: UnboundedSender<ControlRequest>,
+    status_rx: watch::Receiver<Option<Status>>,
+) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    // A client that sends its request slowly or not at all must not keep
+    // the connection open for ever
+    let request = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(request) => request?,
+        Err(_) => return respond(stream.get_mut(), "408 Request Timeout", &error_json("request timed out"), None).await,
+    };
+    let stream = stream.get_mut();
+    let HttpRequest { method, path, origin, host, body } = match request {
+        Ok(request) => request,
+        Err(status) => return respond(stream, status, &error_json("request too large"), None).await,
+    };
+
+    if !host_allowed(host.as_deref(), addr) {
+        return respond(stream, "421 Misdirected Request", &error_json("unknown host"), None).await;
+    }
+    if !origin_allowed(origin.as_deref(), host.as_deref(), allow_origin.as_deref()) {
+        return respond(stream, "403 Forbidden", &error_json("origin not allowed"), None).await;
+    }
+    // Only the allowed origin may read the responses from another page
+    let cors = origin.as_deref().filter(|origin| origin_allowed(Some(origin), None, allow_origin.as_deref()));
+
+    let request = match (method.as_str(), path.as_str()) {
+        ("GET", "/events") => return stream_events(stream, status_rx, cors).await,
+        ("GET", "/status") => Request::Status,
+        ("POST", "/pause") => Request::Pause,
+        ("POST", "/resume") => Request::Resume,
+        ("POST", "/add") => Request::Add { duration: duration_from_body(&body) },
+        // The preflight a browser sends before a JSON POST from another page
+        ("OPTIONS", "/events" | "/status" | "/pause" | "/resume" | "/add") if cors.is_some() => {
+            return preflight(stream, cors).await;
+        }
+        (_, "/events" | "/status" | "/pause" | "/resume" | "/add") => {
+            return respond(stream, "405 Method Not Allowed", &error_json("method not allowed"), cors).await;
+        }
+        _ => return respond(stream, "404 Not Found", &error_json("not found"), cors).await,
+    };
+
+    let control = match request.into_control() {
+        Ok(control) => control,
+        Err(e) => return respond(stream, "400 Bad Request", &error_json(&e), cors).await,
+    };
+    match dispatch(&tx, control).await {
+        Ok(status) => respond(stream, "200 OK", &serde_json::to_string(&status)?, cors).await,
+        Err(e) => respond(stream, "503 Service Unavailable", &error_json(&e), cors).await,
+    }
+}
+
+/// The duration to add, from a plain text or JSON body.
+pub fn duration_from_body(body: &str) -> String {
+    #[derive(Deserialize)]
+    struct AddBody {
+        duration: String,
+    }
+
+    match serde_json::from_str::<AddBody>(body) {
+        Ok(add) => add.duration,
+        Err(_) => body.trim().to_string(),
+    }
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// The `Access-Control-Allow-Origin` header for `cors`, if any.
+fn cors_header(cors: Option<&str>) -> String {
+    cors.map(|origin| format!("Access-Control-Allow-Origin: {}\r\nVary: Origin\r\n", origin))
+        .unwrap_or_default()
+}
+
+/// Allow the allowed origin to POST with a JSON body.
+async fn preflight(stream: &mut TcpStream, cors: Option<&str>) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 204 No Content\r\n{}Access-Control-Allow-Methods: GET, POST\r\nAccess-Control-Allow-Headers: Content-Type\r\nAccess-Control-Max-Age: 600\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        cors_header(cors)
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str, cors: Option<&str>) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        cors_header(cors),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Send the status as an SSE `data:` line now and whenever it changes, until
+/// the countdown ends or the client goes away.
+async fn stream_events(stream: &mut TcpStream, mut status_rx: watch::Receiver<Option<Status>>, cors: Option<&str>) -> io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n{}Connection: close\r\n\r\n",
+        cors_header(cors)
+    );
+    stream.write_all(head.as_bytes()).await?;
+
+    loop {
+        let status = status_rx.borrow_and_update().clone();
+        if let Some(status) = status {
+            let event = format!("data: {}\n\n", serde_json::to_string(&status)?);
+            stream.write_all(event.as_bytes()).await?;
+        }
+        if status_rx.changed().await.is_err() {
+            break;
+        }
+    }
+    stream.shutdown().await
+}
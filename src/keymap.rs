@@ -0,0 +1,274 @@
+use crate::control::{Control, ADD_TIME_STEP};
+use crate::summary::EndReason;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+
+/// What a key does while the countdown runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    TogglePause,
+    Lap,
+    Reset,
+    AddTime,
+    SubtractTime,
+    ToggleTitle,
+    ToggleProgress,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::TogglePause,
+        Action::Lap,
+        Action::Reset,
+        Action::AddTime,
+        Action::SubtractTime,
+        Action::ToggleTitle,
+        Action::ToggleProgress,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// The name used in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::TogglePause => "pause",
+            Action::Lap => "lap",
+            Action::Reset => "reset",
+            Action::AddTime => "add",
+            Action::SubtractTime => "subtract",
+            Action::ToggleTitle => "toggle-title",
+            Action::ToggleProgress => "toggle-progress",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// One word for the footer.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::ToggleTitle => "title",
+            Action::ToggleProgress => "progress",
+            action => action.name(),
+        }
+    }
+
+    /// What the help overlay says about it.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::TogglePause => "Pause or resume",
+            Action::Lap => "Record a lap",
+            Action::Reset => "Start over from the full time",
+            Action::AddTime => "Add a minute",
+            Action::SubtractTime => "Take a minute off",
+            Action::ToggleTitle => "Show or hide the title",
+            Action::ToggleProgress => "Show or hide the progress bar",
+            Action::Help => "Show this help",
+            Action::Quit => "Stop the countdown",
+        }
+    }
+
+    /// The change to the countdown, for the actions that make one.
+    pub fn control(self) -> Option<Control> {
+        match self {
+            Action::TogglePause => Some(Control::TogglePause),
+            Action::Lap => Some(Control::Lap),
+            Action::Reset => Some(Control::Reset),
+            Action::AddTime => Some(Control::AddTime(ADD_TIME_STEP)),
+            Action::SubtractTime => Some(Control::SubtractTime(ADD_TIME_STEP)),
+            Action::Quit => Some(Control::Stop(EndReason::Cancelled)),
+            Action::ToggleTitle | Action::ToggleProgress | Action::Help => None,
+        }
+    }
+}
+
+/// A key together with the modifiers that have to be held.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub const fn plain(code: KeyCode) -> Self {
+        Self { code, modifiers: KeyModifiers::NONE }
+    }
+
+    pub const fn ctrl(ch: char) -> Self {
+        Self { code: KeyCode::Char(ch), modifiers: KeyModifiers::CONTROL }
+    }
+
+    /// Parse a key as written in the config file: a single character such
+    /// as `q` or `+`, or a name such as `space`, `esc` or `f5`, optionally
+    /// after `ctrl+` or `alt+`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut rest = input;
+        let mut modifiers = KeyModifiers::NONE;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            // Terminals report Ctrl with the lower case letter
+            (Some(ch), None) if modifiers.contains(KeyModifiers::CONTROL) => KeyCode::Char(ch.to_ascii_lowercase()),
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key '{}'", input)),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether `event` is this key. Shift is part of the character itself,
+    /// so it only counts for keys that are not characters.
+    pub fn matches(self, event: &KeyEvent) -> bool {
+        let mut modifiers = event.modifiers;
+        if matches!(event.code, KeyCode::Char(_)) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        self.code == event.code && self.modifiers == modifiers
+    }
+
+    /// The key as the help shows it, such as `Space` or `Ctrl+C`.
+    pub fn name(self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(ch) if self.modifiers.contains(KeyModifiers::CONTROL) => ch.to_ascii_uppercase().to_string(),
+            KeyCode::Char(ch) => ch.to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            code => format!("{:?}", code),
+        };
+        let mut name = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            name.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            name.push_str("Alt+");
+        }
+        name + &key
+    }
+}
+
+/// Which key does what. The event loop dispatches on it, and the help
+/// overlay and footer are made from it, so they cannot disagree.
+/// Bindings from the `[keys]` table of the config file go on top of the
+/// defaults.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                (Key::plain(KeyCode::Char(' ')), Action::TogglePause),
+                (Key::plain(KeyCode::Char('p')), Action::TogglePause),
+                (Key::plain(KeyCode::Char('?')), Action::Help),
+                (Key::plain(KeyCode::Char('q')), Action::Quit),
+                (Key::plain(KeyCode::Esc), Action::Quit),
+                (Key::ctrl('c'), Action::Quit),
+                (Key::plain(KeyCode::Char('l')), Action::Lap),
+                (Key::plain(KeyCode::Char('r')), Action::Reset),
+                (Key::plain(KeyCode::Char('+')), Action::AddTime),
+                (Key::plain(KeyCode::Char('=')), Action::AddTime),
+                (Key::plain(KeyCode::Char('-')), Action::SubtractTime),
+                (Key::plain(KeyCode::Char('t')), Action::ToggleTitle),
+                (Key::plain(KeyCode::Char('b')), Action::ToggleProgress),
+            ],
+        }
+    }
+}
+
+impl Keymap {
+    /// The defaults with `overrides` from the config file on top, each
+    /// mapping a key to the name of an action, or to `none` to unbind it.
+    pub fn with_overrides(overrides: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut keymap = Self::default();
+        for (key, name) in overrides {
+            let key = Key::parse(key)?;
+            keymap.bindings.retain(|(bound, _)| *bound != key);
+            if name == "none" {
+                continue;
+            }
+            let action = Action::ALL
+                .into_iter()
+                .find(|action| action.name() == name)
+                .ok_or_else(|| format!("unknown action '{}' for key '{}'", name, key.name()))?;
+            keymap.bindings.push((key, action));
+        }
+        Ok(keymap)
+    }
+
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.bindings.iter().find(|(key, _)| key.matches(event)).map(|(_, action)| *action)
+    }
+
+    /// Every action with the names of all its keys, such as
+    /// `("Esc, Ctrl+C", "Stop the countdown")`, in the order they are bound.
+    pub fn help(&self) -> Vec<(String, &'static str)> {
+        let mut actions: Vec<Action> = Vec::new();
+        for (_, action) in &self.bindings {
+            if !actions.contains(action) {
+                actions.push(*action);
+            }
+        }
+        actions
+            .into_iter()
+            .map(|action| {
+                let keys: Vec<String> = self
+                    .bindings
+                    .iter()
+                    .filter(|(_, bound)| *bound == action)
+                    .map(|(key, _)| key.name())
+                    .collect();
+                (keys.join(", "), action.description())
+            })
+            .collect()
+    }
+
+    /// The first key of every action with a word about it, such as
+    /// `Space pause`, for the footer.
+    pub fn footer(&self) -> Vec<String> {
+        let mut actions: Vec<Action> = Vec::new();
+        let mut hints = Vec::new();
+        for (key, action) in &self.bindings {
+            if !actions.contains(action) {
+                actions.push(*action);
+                hints.push(format!("{} {}", key.name(), action.label()));
+            }
+        }
+        hints
+    }
+}
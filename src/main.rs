@@ -1,36 +1,102 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::time::Duration;
-use tokio::time::interval;
-use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, poll},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand,
-};
-use std::io::stdout;
+use tokio::time::{interval, interval_at};
+use crossterm::event::{self, Event, KeyEventKind, poll};
+use std::io::IsTerminal;
+use std::pin::Pin;
+use std::time::Instant;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::time::Sleep;
 
+mod announce;
+mod backend;
+mod config;
+mod control;
+mod events;
 mod font;
+mod hooks;
+mod http;
+mod frame;
+mod headless;
+mod history;
+mod keymap;
 mod ui;
 mod timer;
 mod time_parser;
+mod threshold;
+mod title;
+mod notify;
+mod osc;
+mod schedule;
+mod start;
+#[cfg(unix)]
+mod socket;
+mod summary;
+mod terminal;
 mod test;
 
-use ui::{Display, render_countdown, render_paused, clear_screen};
+use ui::{Display, DigitStyle, Progress, format_duration, render_countdown, render_paused, render_times_up};
 use timer::{CountdownState, TimerMode};
-use time_parser::parse_duration_or_time;
+use time_parser::{parse_duration_or_time, parse_duration_string};
+use threshold::{Thresholds, Urgency};
+use announce::Announcer;
+use schedule::Schedule;
+use notify::Notifier;
+use hooks::{HookReason, Hooks, ThresholdHook};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use summary::{EndReason, Summary, SummaryFormat};
+use control::{Control, ControlRequest, Remote, Request, Status};
+use terminal::TerminalGuard;
+use headless::{LinePrinter, LineStyle};
+use events::{EventLog, EventTarget};
+use title::{Title, TitleAlign, TitlePosition};
+use history::{Entry, History};
+use keymap::{Action, Keymap};
+use config::Config;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "countdown-tui")]
+#[command(args_conflicts_with_subcommands = true)]
 #[command(about = "A terminal countdown timer with big digital display", long_about = None)]
 #[command(
     after_help = "Examples:
   countdown-tui 25s
+  countdown-tui
   countdown-tui -t \"Coffee Break\" 14:15
   countdown-tui 02:15PM
   countdown-tui -u 30s
-  countdown-tui -s 10s"
+  countdown-tui -s 10s
+  countdown-tui --announce 5m,1m,30s,10..1 10m
+  countdown-tui --warn 5m --critical 1m 10m
+  countdown-tui -p -t \"Standup\" 15m
+  countdown-tui --notify -t \"Tea\" 4m
+  countdown-tui -w -t \"Pasta\" 9m
+  countdown-tui --on 1m \"notify-send 'One minute'\" --exec \"mpv alarm.ogg\" 10m
+  countdown-tui --summary json 25m
+  countdown-tui ctl add 5m
+  countdown-tui --http 127.0.0.1:7777 -t \"Retro\" 1h
+  countdown-tui --headless --interval 1m 1h >> build.log
+  countdown-tui --events json=/tmp/timer.ndjson 25m
+  countdown-tui --inline=3 5m
+  countdown-tui status
+  countdown-tui --hints 25m
+  countdown-tui --config ~/timers/keys.toml 25m
+  countdown-tui --window-title --taskbar-progress -t \"Coffee Break\" 15m
+
+Exit status:
+  0      the countdown finished
+  1      cancelled from the keyboard
+  2      error, such as an invalid duration
+  3      finished, and the --wait screen was dismissed after the deadline
+  128+N  cancelled by signal N"
 )]
 struct Args {
-    #[arg(value_name = "DURATION")]
+    #[command(subcommand)]
+    command: Option<Command>,
+    
+    #[arg(value_name = "DURATION", help = "Duration or target time (e.g., 25s, 1m30s, 14:15, 02:30PM), or leave it out to pick one on a start screen")]
     duration: Option<String>,
 
     #[arg(short = 'u', long = "up", help = "Count up from zero")]
@@ -38,181 +104,906 @@ struct Args {
 
     #[arg(short = 's', long = "say", help = "Announce the time left")]
     say: bool,
+    
+    #[arg(long, value_name = "COMMAND", help = "Speech command for --say: say, espeak-ng, espeak, spd-say or a template like \"piper -t {}\"")]
+    voice_cmd: Option<String>,
+    
+    #[arg(long, value_name = "LIST", value_parser = parse_schedule_arg, help = "When to announce, e.g. 10m,5m,1m,30s,10..1 [default: 10..1 when counting down]")]
+    announce: Option<Schedule>,
 
     #[arg(short = 't', long = "title", value_name = "TEXT", help = "Display title below the countdown")]
     title: Option<String>,
     
+    #[arg(long, value_name = "POSITION", value_enum, default_value_t = TitlePosition::Below, help = "Where to put the title")]
+    title_position: TitlePosition,
+    
+    #[arg(long, value_name = "ALIGN", value_enum, default_value_t = TitleAlign::Center, help = "How to align the title")]
+    title_align: TitleAlign,
+    
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_arg, help = "Turn the digits yellow when this much time is left")]
+    warn: Option<Duration>,
+    
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_arg, help = "Turn the digits red when this much time is left")]
+    critical: Option<Duration>,
+    
+    #[arg(long, help = "Pulse the digits during the last 10 seconds")]
+    blink: bool,
+    
+    #[arg(long, help = "Send a desktop notification when the countdown ends")]
+    notify: bool,
+    
+    #[arg(long, help = "Also notify when the --warn and --critical thresholds are reached")]
+    notify_warnings: bool,
+    
+    #[arg(long, help = "Ring the terminal bell and send OSC 9/777 notifications when the countdown ends")]
+    bell: bool,
+    
+    #[arg(long, help = "Show the main keys in a dim line along the bottom (? shows all of them)")]
+    hints: bool,
+    
+    #[arg(long, value_name = "PATH", help = "Read settings such as key bindings from this file [default: ~/.config/countdown-tui/config.toml]")]
+    config: Option<PathBuf>,
+    
+    #[arg(long, help = "Show the time in the terminal window title, restoring the old title on exit")]
+    window_title: bool,
+    
+    #[arg(long, help = "Show the progress in the taskbar or tab with OSC 9;4 (Windows Terminal, ConEmu, Ghostty)")]
+    taskbar_progress: bool,
+    
+    #[arg(short = 'w', long, help = "Show a flashing TIME'S UP screen at the end and wait for a key")]
+    wait: bool,
+    
+    #[arg(long, value_name = "COMMAND", help = "Run a shell command when the countdown ends or is cancelled")]
+    exec: Option<String>,
+    
+    #[arg(long, requires = "exec", help = "Exit with the status of the --exec command")]
+    exec_status: bool,
+    
+    #[arg(long, num_args = 2, value_names = ["DURATION", "COMMAND"], help = "Run a shell command when this much time is left (repeatable)")]
+    on: Vec<String>,
+    
+    #[arg(short = 'p', long = "progress", help = "Show a progress bar with percentage and ETA")]
+    progress: bool,
+    
+    #[arg(long, value_name = "FORMAT", value_enum, help = "Print a summary of the countdown when it ends")]
+    summary: Option<SummaryFormat>,
+    
+    #[arg(long, value_name = "ADDR", help = "Serve an HTTP API and event stream on this address, e.g. 127.0.0.1:7777")]
+    http: Option<SocketAddr>,
+    
+    #[arg(long, value_name = "ORIGIN", requires = "http", help = "Let web pages from this origin use the HTTP API, e.g. http://localhost:8080")]
+    http_allow_origin: Option<String>,
+    
+    #[arg(long, value_name = "LINES", num_args = 0..=1, require_equals = true, help = "Draw in this many lines below the prompt instead of the whole screen [default: enough for the digits]")]
+    inline: Option<Option<u16>>,
+    
+    #[arg(long, help = "Print status lines instead of the full screen display (the default when stdout is not a terminal)")]
+    headless: bool,
+    
+    #[arg(long, help = "Print a compact line like \"⏱ 12:34 Coffee Break\" every tick instead of the display, for status bars")]
+    status_line: bool,
+    
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_arg, default_value = "1s", help = "How often --headless and --status-line print the time")]
+    interval: Duration,
+    
+    #[arg(long, value_name = "FORMAT[=PATH]", value_parser = EventTarget::parse, help = "Write events as JSON lines to stdout, replacing the display, or to a file with json=PATH")]
+    events: Option<EventTarget>,
+    
     #[arg(long, help = "Run internal tests")]
     test: bool,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Control a running countdown
+    Ctl {
+        #[arg(long, help = "Process ID of the countdown, needed when several are running")]
+        pid: Option<u32>,
+        
+        #[command(subcommand)]
+        request: Request,
+    },
+    /// Print one compact line per running countdown, for status bars
+    Status {
+        #[arg(long, help = "Process ID of the countdown, instead of all of them")]
+        pid: Option<u32>,
+    },
+}
+
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+async fn main() {
+    let exit_code = match run(Args::parse()).await {
+        Ok(exit_code) => exit_code,
+        Err(e) => {
+            eprintln!("{}", e);
+            summary::EXIT_ERROR
+        }
+    };
     
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+}
+
+/// Run the program and return its exit status.
+async fn run(mut args: Args) -> Result<i32, Box<dyn std::error::Error>> {
     if args.test {
         test::test_duration_parsing();
         test::test_timer_logic();
         test::test_font_display();
-        return Ok(());
+        test::test_thresholds();
+        test::test_progress_bar();
+        test::test_frame_diff();
+        test::test_render_backends();
+        test::test_title_wrapping();
+        test::test_announcers();
+        test::test_announcement_schedule();
+        test::test_notifications();
+        test::test_terminal_notifications();
+        test::test_threshold_hooks();
+        test::test_summary();
+        test::test_control_protocol();
+        test::test_http_api();
+        test::test_headless_lines();
+        test::test_event_stream();
+        test::test_digit_fonts();
+        test::test_start_screen();
+        test::test_keymap();
+        return Ok(0);
+    }
+    
+    match args.command {
+        Some(Command::Ctl { pid, request }) => return ctl(pid, request),
+        Some(Command::Status { pid }) => return print_status_lines(pid),
+        None => {}
+    }
+    
+    let config = Config::load(args.config.as_deref())?;
+    let keymap = Keymap::with_overrides(&config.keys).map_err(|e| format!("error: invalid [keys] in the config: {}", e))?;
+    
+    // Raw mode and the alternate screen only make sense on a terminal
+    let headless = args.headless || args.status_line || !std::io::stdout().is_terminal();
+    let events_on_stdout = args.events.as_ref().is_some_and(|target| target.path.is_none());
+    
+    // Signals are caught from here on, so the terminal is put back even
+    // when one arrives on the start screen
+    let (control_tx, mut control_rx) = tokio::sync::mpsc::unbounded_channel();
+    control::forward_signals(control_tx.clone())?;
+    
+    // Without a DURATION, ask for one when there is someone to ask
+    let mut history = History::load();
+    if args.duration.is_none() && !headless && !events_on_stdout && std::io::stdin().is_terminal() {
+        let picked = {
+            let _terminal = TerminalGuard::enter()?;
+            start::prompt(&history, args.title.as_deref(), &mut control_rx)?
+        };
+        let entry = match picked {
+            Ok(entry) => entry,
+            Err(reason) => return Ok(reason.exit_code()),
+        };
+        args.duration = Some(entry.duration);
+        args.title = entry.title;
     }
     
     let duration_str = args.duration.ok_or("error: DURATION is required\n\nUSAGE:\n    countdown-tui [OPTIONS] <DURATION>\n\nFor more information try '--help'")?;
     let duration = parse_duration_or_time(&duration_str)
         .map_err(|e| format!("error: invalid duration or time format '{}'\n\nSupported formats:\n  Duration: 25s, 1m30s, 1h2m3s\n  Time: 14:15, 02:30PM, 10:00AM\n\nOriginal error: {}", duration_str, e))?;
     
-    enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
-    stdout().execute(crossterm::cursor::Hide)?;
+    let asked_to_speak = args.say || args.announce.is_some();
+    let schedule = args.announce.unwrap_or_else(|| Schedule::default_for(if args.up { TimerMode::CountUp } else { TimerMode::CountDown }));
+    // With nothing to say there is no need for something to say it with
+    let speak = asked_to_speak && !schedule.is_empty();
+    let announcer = match (&args.voice_cmd, speak) {
+        (Some(voice_cmd), _) => Some(announce::from_voice_cmd(voice_cmd).ok_or("error: --voice-cmd must not be empty")?),
+        (None, true) => {
+            // Silence is no reason to refuse to count down, as before
+            let detected = announce::detect();
+            if detected.is_none() {
+                let flag = if args.say { "--say" } else { "--announce" };
+                eprintln!("warning: {} needs one of say, espeak-ng, espeak or spd-say on PATH, or a --voice-cmd; going on without announcements", flag);
+            }
+            detected
+        }
+        (None, false) => None,
+    };
+    let notifier = if args.notify || args.notify_warnings {
+        Some(Arc::new(Notifier::detect().ok_or("error: --notify needs gdbus with a session bus, or notify-send on PATH")?))
+    } else {
+        None
+    };
     
-    let exit_code = run_countdown(duration, args.up, args.say, args.title).await?;
+    let mut threshold_hooks = Vec::new();
+    for pair in args.on.chunks(2) {
+        let at = parse_duration_string(&pair[0]).map_err(|e| format!("error: invalid --on duration '{}': {}", pair[0], e))?;
+        threshold_hooks.push(ThresholdHook { at, command: pair[1].clone() });
+    }
+    let hooks = Hooks {
+        on_exit: args.exec,
+        thresholds: threshold_hooks,
+        propagate_status: args.exec_status,
+    };
     
-    stdout().execute(LeaveAlternateScreen)?;
-    disable_raw_mode()?;
-    stdout().execute(crossterm::cursor::Show)?;
+    let event_log = match &args.events {
+        Some(target) => Some(EventLog::open(target).map_err(|e| format!("error: cannot open the event log: {}", e))?),
+        None => None,
+    };
     
-    if exit_code != 0 {
-        std::process::exit(exit_code);
+    let alerts = Alerts {
+        announcer,
+        schedule,
+        notifier,
+        notify_warnings: args.notify_warnings,
+        bell: args.bell,
+        hooks,
+        events: event_log,
+    };
+    
+    let (status_tx, status_rx) = tokio::sync::watch::channel(None);
+    if let Some(addr) = args.http {
+        http::serve(addr, args.http_allow_origin.clone(), control_tx.clone(), status_rx)
+            .await
+            .map_err(|e| format!("error: cannot serve HTTP on {}: {}", addr, e))?;
     }
-    Ok(())
+    // The countdown works without its socket, so failing to create one is not fatal
+    #[cfg(unix)]
+    let socket = match socket::ControlSocket::bind(control_tx) {
+        Ok(socket) => Some(socket),
+        Err(e) => {
+            eprintln!("warning: no control socket, ctl and status will not see this countdown: {}", e);
+            None
+        }
+    };
+    
+    let (mut output, terminal) = if events_on_stdout {
+        (Output::Hidden, None)
+    } else if headless {
+        let style = if args.status_line { LineStyle::Compact } else { LineStyle::Log };
+        (Output::Lines(LinePrinter::new(args.interval, style)), None)
+    } else if let Some(lines) = args.inline {
+        let terminal = TerminalGuard::enter_inline()?;
+        let lines = lines.unwrap_or_else(|| inline_height(args.title.is_some(), args.progress));
+        let display = Display::inline(lines).map_err(|e| format!("error: cannot draw inline: {}", e))?;
+        (Output::Screen(display), Some(terminal))
+    } else {
+        let terminal = TerminalGuard::enter()?;
+        (Output::Screen(Display::new()), Some(terminal))
+    };
+    if let Output::Screen(display) = &mut output {
+        if args.hints {
+            display.set_footer(Some(keymap.footer()));
+        }
+        history.add(Entry { duration: duration_str, title: args.title.clone() });
+        // Losing the history is no reason to stop the countdown
+        let _ = history.save();
+    }
+    if let Some(terminal) = &terminal {
+        if args.window_title {
+            terminal.save_title()?;
+        }
+        if args.taskbar_progress {
+            terminal.clear_progress_on_restore();
+        }
+    }
+    
+    let title_layout = Title {
+        text: String::new(),
+        position: args.title_position,
+        align: args.title_align,
+    };
+    let mut view = View {
+        title: args.title.map(|text| Title { text, ..title_layout.clone() }),
+        title_layout,
+        thresholds: Thresholds {
+            warn: args.warn,
+            critical: args.critical,
+            blink: args.blink,
+        },
+        show_progress: args.progress,
+        show_title: true,
+        keep_last_frame: args.inline.is_some(),
+        window_title: args.window_title,
+        taskbar_progress: args.taskbar_progress,
+        keymap,
+    };
+    
+    let mut remote = Remote { requests: control_rx, status: status_tx };
+    let Outcome { reason, state, overtime } = run_countdown(duration, args.up, args.wait, output, &alerts, &mut view, &mut remote).await?;
+    let mut exit_code = reason.exit_code();
+    #[cfg(unix)]
+    if let Some(socket) = &socket {
+        socket.flush().await;
+    }
+    
+    drop(terminal);
+    
+    // Run the exit hook only now so its output lands on the normal screen
+    if let Some(command) = &alerts.hooks.on_exit {
+        let hook_reason = if reason.is_cancelled() { HookReason::Cancelled } else { HookReason::Finished };
+        let command = hooks::hook_command(command, &state, view.title_text(), hook_reason);
+        let ended = status(&state, &view, Some(reason));
+        match hooks::run_exit_hook(command, &mut remote.requests, ended).await? {
+            // Signals still cancel while the hook runs, and end it too
+            (_, Some(signal)) => exit_code = signal.exit_code(),
+            (status, None) if alerts.hooks.propagate_status => exit_code = status.code().unwrap_or(1),
+            _ => {}
+        }
+    }
+    
+    if let Some(format) = args.summary {
+        let summary = Summary { exit_code, ..Summary::new(&state, reason, overtime) };
+        println!("{}", summary.format(format));
+    }
+    
+    Ok(exit_code)
+}
+
+/// Everything besides the timer itself that affects how a frame is drawn.
+struct View {
+    title: Option<Title>,
+    /// Position, alignment and font for a title set while running.
+    title_layout: Title,
+    thresholds: Thresholds,
+    show_progress: bool,
+    show_title: bool,
+    /// The screen stays behind after the countdown, as with `--inline`.
+    keep_last_frame: bool,
+    window_title: bool,
+    taskbar_progress: bool,
+    keymap: Keymap,
+}
+
+impl View {
+    fn title_text(&self) -> Option<&str> {
+        self.title.as_ref().map(|title| title.text.as_str())
+    }
+    
+    /// The title to draw, unless it was hidden with a key.
+    fn title_on_screen(&self) -> Option<Title> {
+        self.title.clone().filter(|_| self.show_title)
+    }
+}
+
+/// Where the countdown is shown.
+enum Output {
+    /// The big digits on the alternate screen.
+    Screen(Display),
+    /// Plain status lines, for `--headless` and outputs that are not terminals.
+    Lines(LinePrinter),
+    /// Nothing, because `--events` has stdout.
+    Hidden,
+}
+
+/// Ways of telling the user about the countdown besides the screen.
+struct Alerts {
+    announcer: Option<Box<dyn Announcer>>,
+    schedule: Schedule,
+    notifier: Option<Arc<Notifier>>,
+    notify_warnings: bool,
+    bell: bool,
+    hooks: Hooks,
+    events: Option<EventLog>,
+}
+
+impl Alerts {
+    fn emit(&self, state: &CountdownState, event: events::Event) {
+        if let Some(log) = &self.events {
+            log.emit(state, event);
+        }
+    }
+}
+
+/// How the countdown ended, and the timer as it was at that moment.
+struct Outcome {
+    reason: EndReason,
+    state: CountdownState,
+    /// How long the time's up screen stayed up after the deadline.
+    overtime: Duration,
 }
 
 async fn run_countdown(
     total_duration: Duration,
     count_up: bool,
-    say_time: bool,
-    title: Option<String>,
-) -> Result<i32, Box<dyn std::error::Error>> {
+    wait: bool,
+    mut output: Output,
+    alerts: &Alerts,
+    view: &mut View,
+    remote: &mut Remote,
+) -> Result<Outcome, Box<dyn std::error::Error>> {
     let mode = if count_up { TimerMode::CountUp } else { TimerMode::CountDown };
     let mut state = CountdownState::new(total_duration, mode);
-    let mut display = Display::new();
     
-    let mut tick_interval = interval(Duration::from_secs(1));
-    let mut timer_deadline = Box::pin(tokio::time::sleep(total_duration));
-    let mut time_left = total_duration;
-    let mut exit_code = 0;
+    // The first tick comes a second in, so every tick matches the time
+    // that has really passed
+    let start = tokio::time::Instant::now();
+    let mut tick_interval = interval_at(start + Duration::from_secs(1), Duration::from_secs(1));
+    let mut timer_deadline = Box::pin(tokio::time::sleep_until(start + total_duration));
+    let mut reason = EndReason::Finished;
+    let mut overtime = Duration::ZERO;
+    let mut urgency = view.thresholds.urgency(state.time_left());
     
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
-    
-    let event_handle = tokio::spawn(async move {
-        loop {
-            // Use non-blocking poll with timeout
-            match poll(Duration::from_millis(100)) {
-                Ok(true) => {
-                    match event::read() {
-                        Ok(event) => {
-                            if event_tx.send(event).is_err() {
-                                break;
-                            }
-                        }
-                        Err(_) => break,
-                    }
-                }
-                Ok(false) => {
-                    // No event available, continue polling
-                    tokio::time::sleep(Duration::from_millis(10)).await;
-                }
-                Err(_) => break,
-            }
-        }
-    });
-    
-    clear_screen()?;
+    // Status lines have no keyboard to read
+    let event_handle = matches!(output, Output::Screen(_)).then(|| tokio::spawn(read_events(event_tx)));
     
     // Initial draw
-    let current_display_duration = if count_up { Duration::ZERO } else { time_left };
-    render_countdown(&mut display, current_display_duration, &title)?;
-    if say_time && !count_up && time_left.as_secs() <= 10 && time_left.as_secs() > 0 {
-        tokio::spawn(say_countdown(time_left.as_secs()));
-    }
+    alerts.emit(&state, events::Event::Started);
+    draw_countdown(&mut output, &state, view)?;
+    announce_time(alerts, &state);
     
     loop {
+        remote.publish(status(&state, view, None));
+        
         tokio::select! {
+            // The deadline goes first, so the last second ends the countdown
+            // rather than showing up as a tick
+            biased;
+            
+            _ = &mut timer_deadline => {
+                // Time's up! Exit like original timer.C - don't draw 00:00, just break
+                let time_left_before = state.time_left();
+                state.finish();
+                if view.keep_last_frame && !wait {
+                    // Except where 00:00 is what stays in the scrollback
+                    draw_countdown(&mut output, &state, view)?;
+                }
+                alerts.emit(&state, events::Event::Finished);
+                run_threshold_hooks(alerts, &state, view, time_left_before);
+                if alerts.bell {
+                    ring_bell(&mut output, &state, view)?;
+                }
+                if let Some(notifier) = &alerts.notifier {
+                    let body = format!("Time's up after {}", format_duration(state.total_duration()));
+                    notifier.send(notification_summary(view), body).await;
+                }
+                remote.publish(status(&state, view, Some(EndReason::Finished)));
+                // Without a screen there is no key to wait for
+                if let Output::Screen(display) = &mut output {
+                    if wait {
+                        show_in_tab(display, view, "TIME'S UP", osc::ProgressState::Error, 1.0)?;
+                        (reason, overtime) = wait_for_key(display, &mut event_rx, &mut remote.requests, &state, view).await?;
+                    }
+                }
+                break;
+            }
+            
             _ = tick_interval.tick() => {
                 if state.is_paused() {
+                    // Keep the pause length on screen up to date
+                    draw_paused(&mut output, &state, view)?;
                     continue;
                 }
                 
                 // Update time_left like original ticker.C logic
-                if time_left > Duration::from_secs(1) {
-                    time_left -= Duration::from_secs(1);
-                } else {
-                    time_left = Duration::ZERO;
-                }
-                
-                let display_duration = if count_up { 
-                    total_duration - time_left 
-                } else { 
-                    time_left 
-                };
-                render_countdown(&mut display, display_duration, &title)?;
+                let time_left_before = state.time_left();
+                state.tick();
+                alerts.emit(&state, events::Event::Tick);
+                run_threshold_hooks(alerts, &state, view, time_left_before);
+                draw_countdown(&mut output, &state, view)?;
+                announce_time(alerts, &state);
                 
-                if say_time && !count_up && time_left.as_secs() <= 10 && time_left.as_secs() > 0 {
-                    tokio::spawn(say_countdown(time_left.as_secs()));
+                let new_urgency = view.thresholds.urgency(state.time_left());
+                if new_urgency != urgency {
+                    urgency = new_urgency;
+                    alerts.emit(&state, events::Event::threshold(urgency));
+                    notify_urgency(alerts, &state, view, urgency);
                 }
             }
             
-            _ = &mut timer_deadline => {
-                // Time's up! Exit like original timer.C - don't draw 00:00, just break
-                break;
+            Some(ControlRequest { control, reply }) = remote.requests.recv() => {
+                let deadline = timer_deadline.deadline();
+                let stop = apply_control(control, &mut output, &mut state, &mut timer_deadline, alerts, view)?;
+                if timer_deadline.deadline() != deadline {
+                    // Keep the ticks in step with the new deadline
+                    tick_interval.reset();
+                }
+                if let Some(reply) = reply {
+                    let _ = reply.send(status(&state, view, stop));
+                }
+                if let Some(stop) = stop {
+                    reason = stop;
+                    break;
+                }
             }
             
-            event = event_rx.recv() => {
-                if let Some(event) = event {
-                    match event {
-                        Event::Key(key) if key.kind == KeyEventKind::Press => {
-                            match key.code {
-                                KeyCode::Char(' ') => {
-                                    if state.is_paused() {
-                                        state.resume();
-                                        // Restart timer with remaining time_left like original start(timeLeft)
-                                        timer_deadline = Box::pin(tokio::time::sleep(time_left));
-                                        let display_duration = if count_up { 
-                                            total_duration - time_left 
-                                        } else { 
-                                            time_left 
-                                        };
-                                        render_countdown(&mut display, display_duration, &title)?;
-                                    } else {
-                                        state.pause();
-                                        // Stop the timer like original stop() function
-                                        timer_deadline = Box::pin(tokio::time::sleep(Duration::from_secs(999999))); // Never expires
-                                        render_paused(&mut display)?;
-                                    }
-                                }
-                                KeyCode::Esc | KeyCode::Char('c') if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) => {
-                                    exit_code = 1;
-                                    break;
+            Some(event) = event_rx.recv() => {
+                match event {
+                    Event::Key(key) if key.kind == KeyEventKind::Press => {
+                        // Any key closes the help, and does nothing else
+                        if let Output::Screen(display) = &mut output {
+                            if display.showing_help() {
+                                display.set_help(None);
+                                redraw(&mut output, &state, view)?;
+                                continue;
+                            }
+                        }
+                        let Some(action) = view.keymap.action(&key) else {
+                            continue;
+                        };
+                        match action {
+                            Action::Help => {
+                                if let Output::Screen(display) = &mut output {
+                                    display.set_help(Some(view.keymap.help()));
                                 }
-                                _ => {}
                             }
+                            Action::ToggleTitle => view.show_title = !view.show_title,
+                            Action::ToggleProgress => view.show_progress = !view.show_progress,
+                            _ => {}
                         }
-                        Event::Resize(_, _) => {
-                            clear_screen()?;
-                            if state.is_paused() {
-                                render_paused(&mut display)?;
-                            } else {
-                                let current_display_duration = state.display_duration();
-                                render_countdown(&mut display, current_display_duration, &title)?;
+                        if let Some(control) = action.control() {
+                            let deadline = timer_deadline.deadline();
+                            let stop = apply_control(control, &mut output, &mut state, &mut timer_deadline, alerts, view)?;
+                            if timer_deadline.deadline() != deadline {
+                                tick_interval.reset();
                             }
+                            if let Some(stop) = stop {
+                                reason = stop;
+                                break;
+                            }
+                        } else {
+                            redraw(&mut output, &state, view)?;
+                        }
+                    }
+                    Event::Resize(_, _) => {
+                        if let Output::Screen(display) = &mut output {
+                            display.invalidate();
                         }
-                        _ => {}
+                        redraw(&mut output, &state, view)?;
                     }
+                    _ => {}
                 }
             }
         }
     }
     
     // Abort the event reading task to prevent further input processing
-    event_handle.abort();
+    if let Some(event_handle) = event_handle {
+        event_handle.abort();
+    }
+    if reason.is_cancelled() {
+        alerts.emit(&state, events::Event::cancelled(reason));
+    }
+    if let Output::Lines(printer) = &mut output {
+        printer.ended(&state, view.title_text(), reason)?;
+    }
+    remote.publish(status(&state, view, Some(reason)));
     
-    Ok(exit_code)
+    Ok(Outcome { reason, state, overtime })
+}
+
+/// Lines `--inline` reserves by default: the big digits, plus the title and
+/// progress bar with a blank line before each.
+fn inline_height(title: bool, progress: bool) -> u16 {
+    let mut lines = font::char_height() as u16;
+    if progress {
+        lines += 2;
+    }
+    if title {
+        lines += 2;
+    }
+    lines
+}
+
+/// Forward terminal events to the countdown until it stops listening.
+async fn read_events(event_tx: UnboundedSender<Event>) {
+    loop {
+        // Use non-blocking poll with timeout
+        match poll(Duration::from_millis(100)) {
+            Ok(true) => {
+                match event::read() {
+                    Ok(event) => {
+                        if event_tx.send(event).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+            Ok(false) => {
+                // No event available, continue polling
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            Err(_) => break,
+        }
+    }
+}
+
+/// Keep the time's up screen flashing until a key is pressed or a stop
+/// request arrives, returning how it ended and how long that took.
+async fn wait_for_key(
+    display: &mut Display,
+    event_rx: &mut UnboundedReceiver<Event>,
+    control_rx: &mut UnboundedReceiver<ControlRequest>,
+    state: &CountdownState,
+    view: &View,
+) -> Result<(EndReason, Duration), Box<dyn std::error::Error>> {
+    let title = &view.title;
+    let deadline = Instant::now();
+    let mut flash_interval = interval(Duration::from_millis(500));
+    let mut flash = false;
+    
+    loop {
+        tokio::select! {
+            _ = flash_interval.tick() => {
+                flash = !flash;
+                render_times_up(display, deadline.elapsed(), title, flash)?;
+            }
+            
+            event = event_rx.recv() => {
+                match event {
+                    Some(Event::Key(key)) if key.kind == KeyEventKind::Press => return Ok((EndReason::Overtime, deadline.elapsed())),
+                    Some(Event::Resize(_, _)) => {
+                        display.invalidate();
+                        render_times_up(display, deadline.elapsed(), title, flash)?;
+                    }
+                    Some(_) => {}
+                    None => return Ok((EndReason::Overtime, deadline.elapsed())),
+                }
+            }
+            
+            Some(ControlRequest { control, reply }) = control_rx.recv() => {
+                // The countdown is over, so only stopping still does anything
+                let stop = match control {
+                    Control::Stop(reason) => Some(reason.after_deadline()),
+                    _ => None,
+                };
+                if let Some(reply) = reply {
+                    let _ = reply.send(status(state, view, Some(stop.unwrap_or(EndReason::Finished))));
+                }
+                if let Some(reason) = stop {
+                    return Ok((reason, deadline.elapsed()));
+                }
+            }
+        }
+    }
+}
+
+/// Apply a control to the running countdown, returning the end reason when
+/// it asks to stop.
+fn apply_control(
+    control: Control,
+    output: &mut Output,
+    state: &mut CountdownState,
+    timer_deadline: &mut Pin<Box<Sleep>>,
+    alerts: &Alerts,
+    view: &mut View,
+) -> Result<Option<EndReason>, Box<dyn std::error::Error>> {
+    match control {
+        Control::TogglePause => toggle_pause(output, state, timer_deadline, alerts, view)?,
+        Control::Pause if !state.is_paused() => toggle_pause(output, state, timer_deadline, alerts, view)?,
+        Control::Resume if state.is_paused() => toggle_pause(output, state, timer_deadline, alerts, view)?,
+        Control::Lap if !state.is_paused() => lap(alerts, state),
+        Control::Pause | Control::Resume | Control::Lap | Control::Status => {}
+        Control::AddTime(extra) => {
+            state.add_time(extra);
+            alerts.emit(state, events::Event::adjusted(extra));
+            restart_deadline(state, timer_deadline);
+            redraw(output, state, view)?;
+        }
+        Control::SubtractTime(less) => {
+            let removed = state.subtract_time(less);
+            alerts.emit(state, events::Event::subtracted(removed));
+            restart_deadline(state, timer_deadline);
+            redraw(output, state, view)?;
+        }
+        Control::Reset => {
+            state.reset();
+            alerts.emit(state, events::Event::Reset);
+            restart_deadline(state, timer_deadline);
+            redraw(output, state, view)?;
+        }
+        Control::SetTitle(text) => {
+            view.title = (!text.is_empty()).then(|| Title { text, ..view.title_layout.clone() });
+            redraw(output, state, view)?;
+        }
+        Control::Stop(reason) => return Ok(Some(reason)),
+    }
+    Ok(None)
+}
+
+/// Move the deadline to match the time left, unless paused, when it is
+/// set again on resuming.
+fn restart_deadline(state: &CountdownState, timer_deadline: &mut Pin<Box<Sleep>>) {
+    if !state.is_paused() {
+        *timer_deadline = Box::pin(tokio::time::sleep(state.time_left()));
+    }
+}
+
+fn status(state: &CountdownState, view: &View, ended: Option<EndReason>) -> Status {
+    Status::new(state, view.title_text(), ended)
+}
+
+fn toggle_pause(
+    output: &mut Output,
+    state: &mut CountdownState,
+    timer_deadline: &mut Pin<Box<Sleep>>,
+    alerts: &Alerts,
+    view: &View,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if state.is_paused() {
+        state.resume();
+        alerts.emit(state, events::Event::Resumed);
+        // Restart timer with remaining time_left like original start(timeLeft)
+        *timer_deadline = Box::pin(tokio::time::sleep(state.time_left()));
+        draw_countdown(output, state, view)
+    } else {
+        state.pause();
+        alerts.emit(state, events::Event::Paused);
+        // Stop the timer like original stop() function
+        *timer_deadline = Box::pin(tokio::time::sleep(Duration::from_secs(999999))); // Never expires
+        draw_paused(output, state, view)
+    }
+}
+
+fn lap(alerts: &Alerts, state: &mut CountdownState) {
+    state.lap();
+    alerts.emit(state, events::Event::lap(state.elapsed()));
+}
+
+fn redraw(output: &mut Output, state: &CountdownState, view: &View) -> Result<(), Box<dyn std::error::Error>> {
+    if state.is_paused() {
+        draw_paused(output, state, view)
+    } else {
+        draw_countdown(output, state, view)
+    }
+}
+
+fn draw_countdown(output: &mut Output, state: &CountdownState, view: &View) -> Result<(), Box<dyn std::error::Error>> {
+    match output {
+        Output::Screen(display) => {
+            let progress = view.show_progress.then(|| progress(state));
+            render_countdown(display, state.display_duration(), &view.title_on_screen(), digit_style(state, &view.thresholds), progress)?;
+            show_time_in_tab(display, state, view)
+        }
+        Output::Lines(printer) => Ok(printer.running(state, view.title_text())?),
+        Output::Hidden => Ok(()),
+    }
+}
+
+fn draw_paused(output: &mut Output, state: &CountdownState, view: &View) -> Result<(), Box<dyn std::error::Error>> {
+    match output {
+        Output::Screen(display) => {
+            render_paused(display, state.display_duration(), &view.title_on_screen(), digit_style(state, &view.thresholds), state.paused_for())?;
+            show_time_in_tab(display, state, view)
+        }
+        Output::Lines(printer) => Ok(printer.paused(state, view.title_text())?),
+        Output::Hidden => Ok(()),
+    }
+}
+
+/// Mirror the time in the window title and taskbar progress, as far as
+/// they were asked for.
+fn show_time_in_tab(display: &mut Display, state: &CountdownState, view: &View) -> Result<(), Box<dyn std::error::Error>> {
+    let time = format_duration(state.display_duration());
+    if state.is_paused() {
+        show_in_tab(display, view, &format!("⏸ {}", time), osc::ProgressState::Paused, progress(state).fraction)
+    } else {
+        show_in_tab(display, view, &time, osc::ProgressState::Normal, progress(state).fraction)
+    }
+}
+
+fn show_in_tab(
+    display: &mut Display,
+    view: &View,
+    text: &str,
+    progress_state: osc::ProgressState,
+    fraction: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if view.window_title {
+        let title = match view.title_text() {
+            Some(title) => format!("{} – {}", text, title),
+            None => text.to_string(),
+        };
+        display.write_escape(&osc::window_title(&title))?;
+    }
+    if view.taskbar_progress {
+        let percent = (fraction.clamp(0.0, 1.0) * 100.0).floor() as u8;
+        display.write_escape(&osc::for_terminal(&osc::progress(progress_state, percent)))?;
+    }
+    Ok(())
+}
+
+fn announce_time(alerts: &Alerts, state: &CountdownState) {
+    if let Some(announcer) = &alerts.announcer {
+        if let Some(text) = alerts.schedule.announcement(state.display_duration(), state.mode()) {
+            announcer.announce(&text);
+        }
+    }
 }
 
-async fn say_countdown(seconds: u64) {
-    if cfg!(target_os = "macos") {
-        let _ = std::process::Command::new("say")
-            .arg(seconds.to_string())
-            .spawn();
+fn notify_urgency(alerts: &Alerts, state: &CountdownState, view: &View, urgency: Urgency) {
+    let Some(notifier) = alerts.notifier.as_ref().filter(|_| alerts.notify_warnings) else {
+        return;
+    };
+    if urgency != Urgency::Normal {
+        let body = format!("{} remaining", format_duration(state.time_left()));
+        let notifier = notifier.clone();
+        let summary = notification_summary(view);
+        tokio::spawn(async move { notifier.send(summary, body).await });
     }
 }
+
+fn run_threshold_hooks(alerts: &Alerts, state: &CountdownState, view: &View, time_left_before: Duration) {
+    for hook in alerts.hooks.crossed(time_left_before, state.time_left()) {
+        hooks::spawn_quietly(hooks::hook_command(&hook.command, state, view.title_text(), HookReason::Threshold));
+    }
+}
+
+fn ring_bell(output: &mut Output, state: &CountdownState, view: &View) -> Result<(), Box<dyn std::error::Error>> {
+    // Escape sequences would only garble status lines
+    let Output::Screen(display) = output else {
+        return Ok(());
+    };
+    let summary = notification_summary(view);
+    let body = format!("Time's up after {}", format_duration(state.total_duration()));
+    
+    display.write_escape(osc::BEL)?;
+    display.write_escape(&osc::for_terminal(&osc::notify9(&format!("{}: {}", summary, body))))?;
+    display.write_escape(&osc::for_terminal(&osc::notify777(&summary, &body)))
+}
+
+fn notification_summary(view: &View) -> String {
+    view.title.as_ref().map_or_else(|| "Countdown".to_string(), |title| title.text.clone())
+}
+
+fn digit_style(state: &CountdownState, thresholds: &Thresholds) -> DigitStyle {
+    let time_left = state.time_left();
+    DigitStyle {
+        urgency: thresholds.urgency(time_left),
+        // Pulse by dimming every other second
+        dim: thresholds.should_blink(time_left) && time_left.as_secs() % 2 == 1,
+    }
+}
+
+fn progress(state: &CountdownState) -> Progress {
+    let total = state.total_duration().as_secs_f64();
+    let fraction = if total > 0.0 { state.elapsed().as_secs_f64() / total } else { 1.0 };
+    let time_left = chrono::Duration::from_std(state.time_left()).unwrap_or_default();
+    Progress {
+        fraction,
+        eta: chrono::Local::now() + time_left,
+    }
+}
+
+/// Send a request to a running countdown and print its status.
+#[cfg(unix)]
+fn ctl(pid: Option<u32>, request: Request) -> Result<i32, Box<dyn std::error::Error>> {
+    println!("{}", socket::send(pid, &request)?);
+    Ok(0)
+}
+
+#[cfg(not(unix))]
+fn ctl(_pid: Option<u32>, _request: Request) -> Result<i32, Box<dyn std::error::Error>> {
+    Err("error: ctl needs Unix domain sockets, which this platform lacks".into())
+}
+
+/// Print the status line of the countdown running as `pid`, or of every
+/// running countdown. With none running, print nothing and exit with 1 so
+/// a status bar shows an empty block.
+#[cfg(unix)]
+fn print_status_lines(pid: Option<u32>) -> Result<i32, Box<dyn std::error::Error>> {
+    let pids = match pid {
+        Some(pid) => vec![pid],
+        None => socket::running().map_err(|e| format!("error: cannot list the countdowns: {}", e))?,
+    };
+    if pids.is_empty() {
+        return Ok(1);
+    }
+    for pid in pids {
+        let status: Status = serde_json::from_str(&socket::send(Some(pid), &Request::Status)?)?;
+        println!("{}", headless::status_line(&status));
+    }
+    Ok(0)
+}
+
+#[cfg(not(unix))]
+fn print_status_lines(_pid: Option<u32>) -> Result<i32, Box<dyn std::error::Error>> {
+    Err("error: status needs Unix domain sockets, which this platform lacks".into())
+}
+
+fn parse_schedule_arg(input: &str) -> Result<Schedule, String> {
+    Schedule::parse(input).map_err(|e| e.to_string())
+}
+
+fn parse_duration_arg(input: &str) -> Result<Duration, String> {
+    parse_duration_string(input).map_err(|e| e.to_string())
+}
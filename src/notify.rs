@@ -0,0 +1,114 @@
+use crate::announce::find_on_path;
+use std::env;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+const APP_NAME: &str = "countdown-tui";
+
+/// One way of putting a desktop notification on screen.
+pub trait NotifyCommand: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// The command that shows a notification with `summary` and `body`.
+    fn command(&self, summary: &str, body: &str) -> Command;
+}
+
+/// Calls `org.freedesktop.Notifications.Notify` on the session bus through
+/// `gdbus`. The bus comes from `DBUS_SESSION_BUS_ADDRESS`, so pointing that
+/// at a stand-in bus is enough to test without a desktop.
+pub struct DBus;
+
+impl NotifyCommand for DBus {
+    fn name(&self) -> &'static str {
+        "gdbus"
+    }
+
+    fn command(&self, summary: &str, body: &str) -> Command {
+        let mut command = Command::new("gdbus");
+        command
+            .args(["call", "--session", "--timeout", "3"])
+            .args(["--dest", "org.freedesktop.Notifications"])
+            .args(["--object-path", "/org/freedesktop/Notifications"])
+            .args(["--method", "org.freedesktop.Notifications.Notify"])
+            // app_name, replaces_id, app_icon, summary, body, actions, hints, expire_timeout
+            .arg(gvariant_string(APP_NAME))
+            .arg("uint32 0")
+            .arg(gvariant_string(""))
+            .arg(gvariant_string(summary))
+            .arg(gvariant_string(body))
+            .arg("@as []")
+            .arg("@a{sv} {}")
+            .arg("int32 -1");
+        command
+    }
+}
+
+/// libnotify's `notify-send`, for systems without `gdbus`.
+pub struct NotifySend;
+
+impl NotifyCommand for NotifySend {
+    fn name(&self) -> &'static str {
+        "notify-send"
+    }
+
+    fn command(&self, summary: &str, body: &str) -> Command {
+        let mut command = Command::new("notify-send");
+        command.arg(format!("--app-name={}", APP_NAME)).arg(summary).arg(body);
+        command
+    }
+}
+
+/// Quote `text` as a GVariant string literal so gdbus never mistakes it for
+/// a number or another type.
+fn gvariant_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Sends desktop notifications, falling back through its commands in order
+/// until one succeeds.
+pub struct Notifier {
+    commands: Vec<Box<dyn NotifyCommand>>,
+}
+
+impl Notifier {
+    pub fn new(commands: Vec<Box<dyn NotifyCommand>>) -> Self {
+        Self { commands }
+    }
+
+    /// D-Bus when a session bus is configured, then `notify-send`.
+    pub fn detect() -> Option<Self> {
+        let mut commands: Vec<Box<dyn NotifyCommand>> = Vec::new();
+        if env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some() && find_on_path("gdbus") {
+            commands.push(Box::new(DBus));
+        }
+        if find_on_path("notify-send") {
+            commands.push(Box::new(NotifySend));
+        }
+        if commands.is_empty() {
+            None
+        } else {
+            Some(Self::new(commands))
+        }
+    }
+
+    /// Try each command in turn and return the name of the one that worked.
+    pub fn send_blocking(&self, summary: &str, body: &str) -> Option<&'static str> {
+        self.commands.iter().find_map(|notify| {
+            let status = notify
+                .command(summary, body)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+            status.is_ok_and(|status| status.success()).then(|| notify.name())
+        })
+    }
+
+    /// Send a notification without blocking the event loop, giving up after
+    /// a few seconds.
+    pub async fn send(self: &std::sync::Arc<Self>, summary: String, body: String) {
+        let notifier = self.clone();
+        let sending = tokio::task::spawn_blocking(move || notifier.send_blocking(&summary, &body));
+        let _ = tokio::time::timeout(Duration::from_secs(3), sending).await;
+    }
+}
@@ -0,0 +1,73 @@
+use std::env;
+
+pub const BEL: &str = "\x07";
+
+/// OSC 9 notification (iTerm2, ConEmu, Windows Terminal, kitty, WezTerm).
+pub fn notify9(message: &str) -> String {
+    format!("\x1b]9;{}\x07", sanitize(message))
+}
+
+/// OSC 777 notification (urxvt, foot, Ghostty and VTE based terminals).
+/// Its fields are separated by `;`, so any in the text are replaced.
+pub fn notify777(title: &str, body: &str) -> String {
+    format!(
+        "\x1b]777;notify;{};{}\x07",
+        sanitize(title).replace(';', ","),
+        sanitize(body).replace(';', ",")
+    )
+}
+
+/// OSC 0: set the window and tab title.
+pub fn window_title(title: &str) -> String {
+    format!("\x1b]0;{}\x07", sanitize(title))
+}
+
+/// Save the current title on the terminal's title stack (xterm, VTE, kitty,
+/// WezTerm, foot), to be put back with [`POP_TITLE`].
+pub const PUSH_TITLE: &str = "\x1b[22;0t";
+
+/// Restore the title saved with [`PUSH_TITLE`].
+pub const POP_TITLE: &str = "\x1b[23;0t";
+
+/// How the OSC 9;4 progress indicator is coloured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressState {
+    /// Remove the indicator.
+    Hidden = 0,
+    Normal = 1,
+    /// Red in Windows Terminal.
+    Error = 2,
+    /// Yellow in Windows Terminal.
+    Paused = 4,
+}
+
+/// OSC 9;4 taskbar and tab progress (Windows Terminal, ConEmu, Ghostty).
+pub fn progress(state: ProgressState, percent: u8) -> String {
+    format!("\x1b]9;4;{};{}\x07", state as u8, percent.min(100))
+}
+
+/// Whether output goes through tmux, which swallows OSC sequences unless
+/// they are wrapped with [`passthrough`].
+pub fn in_tmux() -> bool {
+    env::var_os("TMUX").is_some()
+}
+
+/// Wrap `sequence` in a tmux DCS passthrough so it reaches the outer
+/// terminal. Every ESC inside has to be doubled.
+pub fn passthrough(sequence: &str) -> String {
+    format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+}
+
+/// Wrap `sequence` for tmux when running inside it.
+pub fn for_terminal(sequence: &str) -> String {
+    if in_tmux() {
+        passthrough(sequence)
+    } else {
+        sequence.to_string()
+    }
+}
+
+/// Drop control characters, which would end the sequence early.
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|ch| !ch.is_control()).collect()
+}
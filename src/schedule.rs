@@ -0,0 +1,140 @@
+use crate::time_parser::{parse_duration_string, ParseError};
+use crate::timer::TimerMode;
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+/// When to announce the time, as points and ranges on the displayed clock:
+/// time left when counting down, time elapsed when counting up.
+#[derive(Clone, Debug, Default)]
+pub struct Schedule {
+    /// Seconds announced as a full sentence such as "five minutes remaining".
+    sentences: BTreeSet<u64>,
+    /// Ranges where every second is counted with just the number, short
+    /// enough to keep up. Kept as ranges, as they can be very long.
+    counts: Vec<RangeInclusive<u64>>,
+}
+
+impl Schedule {
+    /// The announcement schedule used by `--say` on its own, matching the
+    /// original behaviour: the last ten seconds are counted when counting
+    /// down, and nothing is said when counting up.
+    pub fn default_for(mode: TimerMode) -> Self {
+        match mode {
+            TimerMode::CountDown => Self {
+                sentences: BTreeSet::new(),
+                counts: vec![1..=10],
+            },
+            TimerMode::CountUp => Self::default(),
+        }
+    }
+
+    /// Parse a comma separated list such as `10m,5m,1m,30s,10..1`. Plain
+    /// durations are announced as sentences; `a..b` ranges count every
+    /// second between the two ends, which may be bare numbers of seconds.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut schedule = Self::default();
+
+        for item in input.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            if let Some((from, to)) = item.split_once("..") {
+                let from = parse_seconds(from)?;
+                let to = parse_seconds(to)?;
+                schedule.counts.push(from.min(to)..=from.max(to));
+            } else {
+                schedule.sentences.insert(parse_duration_string(item)?.as_secs());
+            }
+        }
+
+        if schedule.is_empty() {
+            return Err(ParseError::InvalidFormat(input.to_string()));
+        }
+        Ok(schedule)
+    }
+
+    /// Whether there is nothing to announce at all.
+    pub fn is_empty(&self) -> bool {
+        self.sentences.is_empty() && self.counts.is_empty()
+    }
+
+    /// What to say when the displayed clock reads `shown`, if anything. A
+    /// sentence wins over a count for the same second.
+    pub fn announcement(&self, shown: Duration, mode: TimerMode) -> Option<String> {
+        let seconds = shown.as_secs();
+        if seconds == 0 {
+            return None;
+        }
+        if self.sentences.contains(&seconds) {
+            let suffix = match mode {
+                TimerMode::CountDown => "remaining",
+                TimerMode::CountUp => "elapsed",
+            };
+            Some(format!("{} {}", duration_words(shown), suffix))
+        } else if self.counts.iter().any(|range| range.contains(&seconds)) {
+            Some(number_words(seconds))
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_seconds(input: &str) -> Result<u64, ParseError> {
+    let input = input.trim();
+    match input.parse::<u64>() {
+        Ok(seconds) => Ok(seconds),
+        Err(_) => parse_duration_string(input).map(|duration| duration.as_secs()),
+    }
+}
+
+/// Spell out a duration, e.g. "one hour thirty minutes".
+pub fn duration_words(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+    let parts = [
+        (total_seconds / 3600, "hour"),
+        ((total_seconds % 3600) / 60, "minute"),
+        (total_seconds % 60, "second"),
+    ];
+
+    let words: Vec<String> = parts
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, unit)| {
+            let plural = if *count == 1 { "" } else { "s" };
+            format!("{} {}{}", number_words(*count), unit, plural)
+        })
+        .collect();
+
+    if words.is_empty() {
+        "zero seconds".to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+/// Spell out a number below one million.
+pub fn number_words(number: u64) -> String {
+    const ONES: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    match number {
+        0..=19 => ONES[number as usize].to_string(),
+        20..=99 => match number % 10 {
+            0 => TENS[(number / 10) as usize].to_string(),
+            ones => format!("{}-{}", TENS[(number / 10) as usize], ONES[ones as usize]),
+        },
+        100..=999 => match number % 100 {
+            0 => format!("{} hundred", ONES[(number / 100) as usize]),
+            rest => format!("{} hundred {}", ONES[(number / 100) as usize], number_words(rest)),
+        },
+        1000..=999_999 => match number % 1000 {
+            0 => format!("{} thousand", number_words(number / 1000)),
+            rest => format!("{} thousand {}", number_words(number / 1000), number_words(rest)),
+        },
+        _ => number.to_string(),
+    }
+}
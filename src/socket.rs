@@ -0,0 +1,172 @@
+use crate::control::{dispatch, ControlRequest, Request, Response};
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::RwLock;
+
+/// Where the control sockets live: `$XDG_RUNTIME_DIR/countdown-tui`, or
+/// `countdown-tui-$UID` in the temporary directory when that is not set.
+pub fn socket_dir() -> PathBuf {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("countdown-tui"),
+        None => env::temp_dir().join(format!("countdown-tui-{}", current_uid())),
+    }
+}
+
+fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+/// Make sure `dir` is a directory of this user's that nobody else can
+/// reach into, so no one else can talk to our countdowns or plant sockets
+/// for `ctl` and `status`.
+pub fn check_private(dir: &Path) -> io::Result<()> {
+    let metadata = std::fs::symlink_metadata(dir)?;
+    let problem = if !metadata.is_dir() {
+        "is not a directory"
+    } else if metadata.uid() != current_uid() {
+        "belongs to another user"
+    } else if metadata.mode() & 0o077 != 0 {
+        "can be reached by other users"
+    } else {
+        return Ok(());
+    };
+    Err(io::Error::new(io::ErrorKind::PermissionDenied, format!("{} {}", dir.display(), problem)))
+}
+
+/// The socket of the countdown running as process `pid`.
+pub fn socket_path(pid: u32) -> PathBuf {
+    socket_dir().join(format!("{}.sock", pid))
+}
+
+/// The control socket of this process, removed again when dropped.
+pub struct ControlSocket {
+    path: PathBuf,
+    /// Held for reading while a request is answered, so shutting down can
+    /// wait for the reply to a `stop`.
+    busy: Arc<RwLock<()>>,
+}
+
+impl ControlSocket {
+    /// Listen on this process's socket, passing every request on to `tx`.
+    pub fn bind(tx: UnboundedSender<ControlRequest>) -> io::Result<Self> {
+        let dir = socket_dir();
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(&dir)?;
+        check_private(&dir)?;
+        let path = socket_path(std::process::id());
+        // A leftover from an earlier process with the same pid
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+        let busy = Arc::new(RwLock::new(()));
+
+        let serving = busy.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, tx.clone(), serving.clone()));
+            }
+        });
+        Ok(Self { path, busy })
+    }
+
+    /// Wait, at most a second, for replies that are still being written.
+    pub async fn flush(&self) {
+        let _ = tokio::time::timeout(Duration::from_secs(1), self.busy.write()).await;
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Answer each request line on `stream` with a response line.
+async fn serve(stream: UnixStream, tx: UnboundedSender<ControlRequest>, busy: Arc<RwLock<()>>) -> io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = tokio::io::BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let _answering = busy.read().await;
+        let control = serde_json::from_str::<Request>(&line)
+            .map_err(|e| format!("invalid request: {}", e))
+            .and_then(Request::into_control);
+        let result = match control {
+            Ok(control) => dispatch(&tx, control).await,
+            Err(e) => Err(e),
+        };
+        let mut response = serde_json::to_string(&Response::from_result(result))?;
+        response.push('\n');
+        writer.write_all(response.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Process IDs of the countdowns whose sockets accept connections.
+pub fn running() -> io::Result<Vec<u32>> {
+    let dir = socket_dir();
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    check_private(&dir)?;
+    let mut pids: Vec<u32> = entries
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.strip_suffix(".sock")?.parse().ok())
+        .filter(|pid| std::os::unix::net::UnixStream::connect(socket_path(*pid)).is_ok())
+        .collect();
+    pids.sort_unstable();
+    Ok(pids)
+}
+
+/// Send `request` to the countdown running as `pid`, or the only one running
+/// when no pid is given, and return its status as a JSON line.
+pub fn send(pid: Option<u32>, request: &Request) -> Result<String, Box<dyn std::error::Error>> {
+    match check_private(&socket_dir()) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(format!("error: cannot reach the countdowns: {}", e).into()),
+        _ => {}
+    }
+    let pid = match pid {
+        Some(pid) => pid,
+        None => match running()?[..] {
+            [pid] => pid,
+            [] => return Err("error: no countdown is running".into()),
+            ref pids => {
+                let pids: Vec<String> = pids.iter().map(u32::to_string).collect();
+                return Err(format!("error: several countdowns are running, pick one with --pid: {}", pids.join(", ")).into());
+            }
+        },
+    };
+
+    let mut stream = std::os::unix::net::UnixStream::connect(socket_path(pid))
+        .map_err(|e| format!("error: cannot reach countdown {}: {}", pid, e))?;
+    writeln!(stream, "{}", serde_json::to_string(request)?)?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    if line.is_empty() {
+        return Err("error: the countdown closed the connection without replying".into());
+    }
+    let response: RawResponse = serde_json::from_str(&line)?;
+    match (response.status, response.error) {
+        (Some(status), _) if response.ok => Ok(status.get().to_string()),
+        (_, error) => Err(format!("error: {}", error.unwrap_or_else(|| "no status in the reply".to_string())).into()),
+    }
+}
+
+/// A `Response` as read back by `send`, with the status kept as it was sent.
+#[derive(serde::Deserialize)]
+struct RawResponse {
+    ok: bool,
+    status: Option<Box<serde_json::value::RawValue>>,
+    error: Option<String>,
+}
@@ -0,0 +1,176 @@
+use crate::control::{Control, ControlRequest};
+use crate::history::{Entry, History};
+use crate::summary::EndReason;
+use crate::time_parser::parse_duration_or_time;
+use crate::ui::{render_start, Display};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// The quick picks offered on the start screen.
+pub const PRESETS: &[&str] = &["1m", "5m", "10m", "15m", "25m", "45m", "1h"];
+
+/// How many recent countdowns the start screen lists.
+const RECENT_SHOWN: usize = 5;
+
+/// The part of the start screen that keys go to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Focus {
+    Duration,
+    Title,
+    Presets(usize),
+    Recent(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StartAction {
+    Start(Entry),
+    Cancel,
+}
+
+/// What is typed and picked on the start screen shown when no DURATION is
+/// given.
+#[derive(Clone, Debug)]
+pub struct StartScreen {
+    pub duration: String,
+    pub title: String,
+    pub focus: Focus,
+    pub recent: Vec<Entry>,
+}
+
+impl StartScreen {
+    /// Start out with `title`, as given with `--title`.
+    pub fn new(history: &History, title: Option<&str>) -> Self {
+        Self {
+            duration: String::new(),
+            title: title.unwrap_or_default().to_string(),
+            focus: Focus::Duration,
+            recent: history.entries.iter().take(RECENT_SHOWN).cloned().collect(),
+        }
+    }
+
+    /// The typed duration as it would run, or why it cannot; `None` while
+    /// nothing is typed.
+    pub fn validation(&self) -> Option<Result<Duration, String>> {
+        let input = self.duration.trim();
+        if input.is_empty() {
+            return None;
+        }
+        Some(parse_duration_or_time(input).map_err(|e| e.to_string()))
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<StartAction> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Some(StartAction::Cancel),
+            KeyCode::Char('c') if ctrl => return Some(StartAction::Cancel),
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Up | KeyCode::BackTab => self.focus = self.previous_focus(),
+            KeyCode::Down | KeyCode::Tab => self.focus = self.next_focus(key.code == KeyCode::Tab),
+            KeyCode::Left => {
+                if let Focus::Presets(index) = self.focus {
+                    self.focus = Focus::Presets(index.saturating_sub(1));
+                }
+            }
+            KeyCode::Right => {
+                if let Focus::Presets(index) = self.focus {
+                    self.focus = Focus::Presets((index + 1).min(PRESETS.len() - 1));
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(field) = self.field() {
+                    field.pop();
+                }
+            }
+            KeyCode::Char(ch) if !ctrl => {
+                if let Some(field) = self.field() {
+                    field.push(ch);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// The text field that has the focus.
+    fn field(&mut self) -> Option<&mut String> {
+        match self.focus {
+            Focus::Duration => Some(&mut self.duration),
+            Focus::Title => Some(&mut self.title),
+            _ => None,
+        }
+    }
+
+    /// Start what has the focus: a preset with the typed title, a recent
+    /// countdown as it was, or the typed duration once it is valid.
+    fn submit(&self) -> Option<StartAction> {
+        let title = Some(self.title.trim().to_string()).filter(|title| !title.is_empty());
+        let entry = match self.focus {
+            Focus::Presets(index) => Entry { duration: PRESETS[index].to_string(), title },
+            Focus::Recent(index) => self.recent[index].clone(),
+            Focus::Duration | Focus::Title => {
+                if !matches!(self.validation(), Some(Ok(_))) {
+                    return None;
+                }
+                Entry { duration: self.duration.trim().to_string(), title }
+            }
+        };
+        Some(StartAction::Start(entry))
+    }
+
+    fn next_focus(&self, wrap: bool) -> Focus {
+        match self.focus {
+            Focus::Duration => Focus::Title,
+            Focus::Title => Focus::Presets(0),
+            Focus::Presets(_) if !self.recent.is_empty() => Focus::Recent(0),
+            Focus::Recent(index) if index + 1 < self.recent.len() => Focus::Recent(index + 1),
+            _ if wrap => Focus::Duration,
+            focus => focus,
+        }
+    }
+
+    fn previous_focus(&self) -> Focus {
+        match self.focus {
+            Focus::Duration | Focus::Title => Focus::Duration,
+            Focus::Presets(_) => Focus::Title,
+            Focus::Recent(0) => Focus::Presets(0),
+            Focus::Recent(index) => Focus::Recent(index - 1),
+        }
+    }
+}
+
+/// Show the start screen until a countdown is picked, or until it is left
+/// with Esc or Ctrl+C or a stop request such as a signal comes in on
+/// `requests`, giving the reason. Other requests are dropped, as there is
+/// no countdown yet for them to change. Expects the terminal to be set up
+/// already.
+pub fn prompt(
+    history: &History,
+    title: Option<&str>,
+    requests: &mut UnboundedReceiver<ControlRequest>,
+) -> Result<Result<Entry, EndReason>, Box<dyn std::error::Error>> {
+    let mut screen = StartScreen::new(history, title);
+    let mut display = Display::new();
+    render_start(&mut display, &screen)?;
+    loop {
+        while let Ok(request) = requests.try_recv() {
+            if let Control::Stop(reason) = request.control {
+                return Ok(Err(reason));
+            }
+        }
+        // Wake up now and then to look for requests
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match screen.handle_key(key) {
+                Some(StartAction::Start(entry)) => return Ok(Ok(entry)),
+                Some(StartAction::Cancel) => return Ok(Err(EndReason::Cancelled)),
+                None => {}
+            },
+            Event::Resize(_, _) => display.invalidate(),
+            _ => {}
+        }
+        render_start(&mut display, &screen)?;
+    }
+}
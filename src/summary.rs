@@ -0,0 +1,106 @@
+use crate::timer::CountdownState;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Exit status when the countdown could not run at all, matching the status
+/// clap uses for bad arguments.
+pub const EXIT_ERROR: i32 = 2;
+
+/// How a countdown ended. Every reason exits with its own status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndReason {
+    /// The deadline passed. Exits with 0.
+    Finished,
+    /// The deadline passed and the `--wait` time's up screen was dismissed,
+    /// with a key or by a stop request or signal. Exits with 3.
+    Overtime,
+    /// Cancelled from the keyboard. Exits with 1.
+    Cancelled,
+    /// Cancelled by this signal. Exits with 128 plus the signal number, like
+    /// a shell reports a process killed by it.
+    Signal(i32),
+}
+
+impl EndReason {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            EndReason::Finished => 0,
+            EndReason::Cancelled => 1,
+            EndReason::Overtime => 3,
+            EndReason::Signal(signal) => 128 + signal,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EndReason::Finished => "finished",
+            EndReason::Overtime => "overtime",
+            EndReason::Cancelled => "cancelled",
+            EndReason::Signal(_) => "signal",
+        }
+    }
+
+    pub fn is_cancelled(self) -> bool {
+        matches!(self, EndReason::Cancelled | EndReason::Signal(_))
+    }
+
+    /// The reason for a stop that arrives on the time's up screen. The
+    /// countdown has finished by then, so stopping only dismisses the screen
+    /// and cannot cancel it any more.
+    pub fn after_deadline(self) -> Self {
+        match self {
+            EndReason::Finished => EndReason::Finished,
+            _ => EndReason::Overtime,
+        }
+    }
+}
+
+/// Output formats for `--summary`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SummaryFormat {
+    Json,
+}
+
+/// What happened during a countdown, printed by `--summary` once the
+/// terminal is back to normal. Durations are whole seconds, like the
+/// `COUNTDOWN_*` variables of the hooks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub reason: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    pub exit_code: i32,
+    pub total: u64,
+    pub elapsed: u64,
+    /// Time spent on the time's up screen after the deadline.
+    pub overtime: u64,
+    pub paused: u64,
+    pub pause_count: u32,
+    /// Elapsed time at each lap.
+    pub laps: Vec<u64>,
+}
+
+impl Summary {
+    pub fn new(state: &CountdownState, reason: EndReason, overtime: Duration) -> Self {
+        Self {
+            reason: reason.as_str(),
+            signal: match reason {
+                EndReason::Signal(signal) => Some(signal),
+                _ => None,
+            },
+            exit_code: reason.exit_code(),
+            total: state.total_duration().as_secs(),
+            elapsed: state.elapsed().as_secs(),
+            overtime: overtime.as_secs(),
+            paused: state.paused_total().as_secs(),
+            pause_count: state.pause_count(),
+            laps: state.laps().iter().map(Duration::as_secs).collect(),
+        }
+    }
+
+    pub fn format(&self, format: SummaryFormat) -> String {
+        match format {
+            SummaryFormat::Json => serde_json::to_string(self).expect("summary serializes to JSON"),
+        }
+    }
+}
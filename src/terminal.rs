@@ -0,0 +1,108 @@
+use crossterm::{
+    cursor,
+    style::Print,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use crate::osc;
+use std::io::{self, stdout, Write};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+const INACTIVE: u8 = 0;
+const FULL_SCREEN: u8 = 1;
+const INLINE: u8 = 2;
+
+/// How the terminal is currently set up.
+static MODE: AtomicU8 = AtomicU8::new(INACTIVE);
+
+/// Whether the window title was saved and has to be put back.
+static TITLE_SAVED: AtomicBool = AtomicBool::new(false);
+
+/// Whether a taskbar progress indicator may be showing.
+static PROGRESS_SHOWN: AtomicBool = AtomicBool::new(false);
+
+/// Puts the terminal into raw mode with the cursor hidden, on the alternate
+/// screen unless inline, and puts it back when dropped. A panic hook does
+/// the same, so the panic message ends up readable on the normal screen.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> io::Result<Self> {
+        install_panic_hook();
+        MODE.store(FULL_SCREEN, Ordering::SeqCst);
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen)?;
+        stdout().execute(cursor::Hide)?;
+        Ok(Self)
+    }
+
+    /// Set up for drawing below the prompt, leaving the screen as it is.
+    pub fn enter_inline() -> io::Result<Self> {
+        install_panic_hook();
+        MODE.store(INLINE, Ordering::SeqCst);
+        enable_raw_mode()?;
+        stdout().execute(cursor::Hide)?;
+        Ok(Self)
+    }
+
+    /// Save the window title so it can be restored when the countdown has
+    /// been showing in it.
+    pub fn save_title(&self) -> io::Result<()> {
+        TITLE_SAVED.store(true, Ordering::SeqCst);
+        write_escape(osc::PUSH_TITLE)
+    }
+
+    /// Remove the taskbar progress indicator again on restore.
+    pub fn clear_progress_on_restore(&self) {
+        PROGRESS_SHOWN.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Put back the window title and remove the progress indicator, leave the
+/// alternate screen, or the inline lines with the last frame left behind,
+/// switch raw mode off and show the cursor again. Does nothing if that has
+/// already happened.
+pub fn restore() {
+    if TITLE_SAVED.swap(false, Ordering::SeqCst) {
+        let _ = write_escape(osc::POP_TITLE);
+    }
+    if PROGRESS_SHOWN.swap(false, Ordering::SeqCst) {
+        let _ = write_escape(&osc::for_terminal(&osc::progress(osc::ProgressState::Hidden, 0)));
+    }
+    match MODE.swap(INACTIVE, Ordering::SeqCst) {
+        FULL_SCREEN => {
+            let _ = stdout().execute(LeaveAlternateScreen);
+        }
+        INLINE => {
+            // The inline display parks the cursor on its last line
+            let _ = stdout().execute(Print("\r\n"));
+        }
+        _ => return,
+    }
+    let _ = disable_raw_mode();
+    let _ = stdout().execute(cursor::Show);
+}
+
+fn write_escape(sequence: &str) -> io::Result<()> {
+    let mut out = stdout();
+    out.write_all(sequence.as_bytes())?;
+    out.flush()
+}
+
+fn install_panic_hook() {
+    static INSTALLED: AtomicBool = AtomicBool::new(false);
+    if INSTALLED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        previous(info);
+    }));
+}
@@ -1,7 +1,28 @@
 use std::time::Duration;
 use crate::time_parser::parse_duration_or_time;
 use crate::timer::{CountdownState, TimerMode};
-use crate::font::get_font;
+use crate::font::{get_font, render_text, render_text_scaled};
+use crate::threshold::{Thresholds, Urgency};
+use crate::ui::{progress_line, Progress};
+use crate::frame::{CellStyle, Frame};
+use crate::announce::from_voice_cmd;
+use crate::schedule::Schedule;
+use crate::notify::{DBus, Notifier, NotifyCommand};
+use crate::osc;
+use crate::hooks::{Hooks, ThresholdHook};
+use crate::control::{Control, Request, Status};
+use crate::http::{duration_from_body, host_allowed, origin_allowed};
+use crate::headless::{status_line, LinePrinter, LineStyle};
+use crate::events::{Event, EventLog, EventTarget};
+use crate::summary::{EndReason, Summary, SummaryFormat};
+use crate::backend::{MemoryBackend, RenderBackend, TextBackend};
+use crate::title::{display_width, wrap_title, Title, TitleAlign, TitlePosition};
+use crate::ui::{render_countdown, render_paused, render_start, render_times_up, DigitFont, DigitStyle, Display};
+use crate::history::{Entry, History};
+use crate::start::{StartAction, StartScreen};
+use crate::keymap::{Action, Key, Keymap};
+use crate::config::Config;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 pub fn test_duration_parsing() {
     println!("Testing duration parsing...");
@@ -87,12 +108,27 @@ pub fn test_timer_logic() {
     countdown.resume();
     println!("After resume: paused = {}", countdown.is_paused());
     
+    countdown.add_time(Duration::from_secs(60));
+    println!("After adding 60s: {}s", countdown.display_duration().as_secs());
+    
+    let removed = countdown.subtract_time(Duration::from_secs(30));
+    println!("After subtracting 30s: {}s ({}s taken off)", countdown.display_duration().as_secs(), removed.as_secs());
+    
+    countdown.reset();
+    println!("After reset: {}s", countdown.display_duration().as_secs());
+    
+    let removed = countdown.subtract_time(Duration::from_secs(3600));
+    println!("After subtracting 1h: {}s ({}s taken off)", countdown.display_duration().as_secs(), removed.as_secs());
+    
     let mut countup = CountdownState::new(Duration::from_secs(10), TimerMode::CountUp);
     println!("\nCount-up mode:");
     println!("Initial display: {}s", countup.display_duration().as_secs());
     
     countup.tick();
     println!("After 1 tick: {}s", countup.display_duration().as_secs());
+    
+    countup.add_time(Duration::from_secs(60));
+    println!("After adding 60s: {}s of {}s", countup.display_duration().as_secs(), countup.total_duration().as_secs());
 }
 
 pub fn test_font_display() {
@@ -116,6 +152,34 @@ pub fn test_font_display() {
         }
     }
     
+    let banner = render_text("PAUSED").unwrap_or_default();
+    let expected = vec!["█▀▄ ▄▀▄ █ █ ▄▀▀ ██▀ █▀▄", "█▀  █▀█ ▀▄█ ▄██ █▄▄ █▄▀"];
+    if banner == expected {
+        println!("✓ PAUSED banner from text font (correct)");
+    } else {
+        println!("✗ PAUSED banner from text font -> {:?} (expected {:?})", banner, expected);
+    }
+    
+    println!("Text font sample:");
+    for line in render_text("Coffee Break: 5 min!").unwrap_or_default() {
+        println!("  {}", line);
+    }
+    
+    if render_text("naïve").is_none() {
+        println!("✓ text font without glyph -> None (correct)");
+    } else {
+        println!("✗ text font without glyph -> rendered (expected None)");
+    }
+    
+    let alike: Vec<char> = ('a'..='z')
+        .filter(|ch| render_text(&ch.to_string()) == render_text(&ch.to_ascii_uppercase().to_string()))
+        .collect();
+    if alike.is_empty() {
+        println!("✓ lower case letters differ from capitals (correct)");
+    } else {
+        println!("✗ lower case letters same as capitals -> {:?}", alike);
+    }
+    
     if let Some(one) = font.get(&'1') {
         println!("Character '1':");
         for (i, line) in one.iter().enumerate() {
@@ -126,4 +190,932 @@ pub fn test_font_display() {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+pub fn test_thresholds() {
+    println!("\nTesting thresholds...");
+    
+    let thresholds = Thresholds {
+        warn: Some(Duration::from_secs(300)),
+        critical: Some(Duration::from_secs(60)),
+        blink: true,
+    };
+    
+    let test_cases = vec![
+        (600, Urgency::Normal, false),
+        (300, Urgency::Warning, false),
+        (61, Urgency::Warning, false),
+        (60, Urgency::Critical, false),
+        (10, Urgency::Critical, true),
+        (0, Urgency::Critical, true),
+    ];
+    
+    for (seconds, expected_urgency, expected_blink) in test_cases {
+        let time_left = Duration::from_secs(seconds);
+        let urgency = thresholds.urgency(time_left);
+        let blink = thresholds.should_blink(time_left);
+        if urgency == expected_urgency && blink == expected_blink {
+            println!("✓ {}s -> {:?}, blink = {} (correct)", seconds, urgency, blink);
+        } else {
+            println!("✗ {}s -> {:?}, blink = {} (expected {:?}, blink = {})", seconds, urgency, blink, expected_urgency, expected_blink);
+        }
+    }
+}
+
+pub fn test_progress_bar() {
+    println!("\nTesting progress bar...");
+    
+    let eta = chrono::Local::now();
+    for fraction in [0.0, 0.25, 0.5, 1.0] {
+        let line = progress_line(Progress { fraction, eta }, 80);
+        println!("  {:>4} -> '{}' (chars: {})", fraction, line, line.chars().count());
+    }
+    
+    let narrow = progress_line(Progress { fraction: 0.5, eta }, 20);
+    if narrow.starts_with("50%") {
+        println!("✓ narrow terminal -> '{}' (correct)", narrow);
+    } else {
+        println!("✗ narrow terminal -> '{}' (expected label only)", narrow);
+    }
+}
+
+pub fn test_frame_diff() {
+    println!("\nTesting frame diff...");
+    
+    let mut previous = Frame::new(10, 3);
+    previous.put_str(2, 1, "12:34", CellStyle::default());
+    
+    let mut next = previous.clone();
+    next.put_str(2, 1, "12:33", CellStyle::default());
+    
+    let runs = next.diff(&previous);
+    if runs.len() == 1 && runs[0].x == 6 && runs[0].y == 1 && runs[0].cells.len() == 1 {
+        println!("✓ one changed cell -> run at ({}, {}) (correct)", runs[0].x, runs[0].y);
+    } else {
+        println!("✗ one changed cell -> {:?} (expected a single run at (6, 1))", runs);
+    }
+    
+    if next.diff(&next).is_empty() {
+        println!("✓ identical frames -> no runs (correct)");
+    } else {
+        println!("✗ identical frames -> runs emitted");
+    }
+    
+    let resized = Frame::new(12, 3);
+    let runs = resized.diff(&previous);
+    if runs.len() == 3 {
+        println!("✓ resized frame -> full redraw (correct)");
+    } else {
+        println!("✗ resized frame -> {} runs (expected 3)", runs.len());
+    }
+    
+    match next.get(6, 1) {
+        Some(cell) if cell.ch == '3' => println!("✓ cell (6, 1) -> '3' (correct)"),
+        other => println!("✗ cell (6, 1) -> {:?} (expected '3')", other),
+    }
+}
+
+pub fn test_render_backends() {
+    println!("\nTesting render backends...");
+    
+    let mut display = Display::with_backend(MemoryBackend::new(60, 16));
+    let title = Some(Title {
+        text: "Coffee Break".to_string(),
+        position: TitlePosition::Below,
+        align: TitleAlign::Center,
+    });
+    if let Err(e) = render_countdown(&mut display, Duration::from_secs(90), &title, DigitStyle::default(), None) {
+        println!("✗ render countdown -> Error: {}", e);
+        return;
+    }
+    
+    let big_title = render_text("Coffee Break").unwrap_or_default();
+    let lines = display.backend().screen().text_lines();
+    if lines.iter().any(|line| line.trim() == big_title[0].trim()) {
+        println!("✓ memory backend -> big title on screen (correct)");
+    } else {
+        println!("✗ memory backend -> big title missing");
+    }
+    for line in &lines {
+        println!("  |{}", line);
+    }
+    
+    if let Err(e) = render_paused(&mut display, Duration::from_secs(90), &title, DigitStyle::default(), Duration::from_secs(12)) {
+        println!("✗ render paused -> Error: {}", e);
+        return;
+    }
+    let lines = display.backend().screen().text_lines();
+    if lines.iter().any(|line| line.trim() == big_title[0].trim()) && lines.iter().any(|line| line.trim() == "paused for 00:12") {
+        println!("✓ paused screen -> digits, pause length and title (correct)");
+    } else {
+        println!("✗ paused screen -> pause length or title missing");
+    }
+    for line in &lines {
+        println!("  |{}", line);
+    }
+    
+    let mut display = Display::with_backend(MemoryBackend::new(60, 10));
+    if let Err(e) = render_countdown(&mut display, Duration::from_secs(90), &title, DigitStyle::default(), None) {
+        println!("✗ render countdown -> Error: {}", e);
+        return;
+    }
+    let lines = display.backend().screen().text_lines();
+    if lines.iter().any(|line| line.trim() == "Coffee Break") {
+        println!("✓ short terminal -> plain title (correct)");
+    } else {
+        println!("✗ short terminal -> plain title missing");
+    }
+
+    let mut display = Display::with_backend(MemoryBackend::new(100, 20));
+    if let Err(e) = render_times_up(&mut display, Duration::from_secs(12), &title, true) {
+        println!("✗ render time's up -> Error: {}", e);
+        return;
+    }
+    let lines = display.backend().screen().text_lines();
+    let banner = render_text_scaled("TIME'S UP", 2).unwrap_or_default();
+    if lines.iter().any(|line| line.trim() == banner[0].trim()) && lines.iter().any(|line| line.trim() == big_title[0].trim()) {
+        println!("✓ time's up screen -> banner, overrun and title (correct)");
+    } else {
+        println!("✗ time's up screen -> banner or title missing");
+    }
+    for line in &lines {
+        println!("  |{}", line);
+    }
+    
+    let mut output = Vec::new();
+    let mut text = TextBackend::new(&mut output, 20, 3);
+    let mut frame = Frame::new(20, 3);
+    frame.put_str(4, 1, "plain text", CellStyle::default());
+    let drawn = text.draw(&frame.diff(&Frame::new(20, 3))).and_then(|_| text.flush());
+    let output = String::from_utf8_lossy(&output);
+    match drawn {
+        Ok(()) if output == "    plain text\n\n" => println!("✓ text backend -> {:?} (correct)", output),
+        Ok(()) => println!("✗ text backend -> {:?} (expected \"    plain text\\n\\n\")", output),
+        Err(e) => println!("✗ text backend -> Error: {}", e),
+    }
+}
+
+pub fn test_title_wrapping() {
+    println!("\nTesting title wrapping...");
+    
+    let test_cases = vec![
+        ("Coffee Break", 20, vec!["Coffee Break"]),
+        ("Coffee Break", 8, vec!["Coffee", "Break"]),
+        ("咖啡休息时间", 8, vec!["咖啡休息", "时间"]),
+        ("Café ☕ time", 20, vec!["Café ☕ time"]),
+        ("one two three four five", 9, vec!["one two", "three", "four five"]),
+        ("one two three four five six", 9, vec!["one two", "three", "four fiv…"]),
+    ];
+    
+    for (input, width, expected) in test_cases {
+        let lines = wrap_title(input, width, 3);
+        let fits = lines.iter().all(|line| display_width(line) <= width);
+        if lines == expected && fits {
+            println!("✓ {:?} @ {} -> {:?} (correct)", input, width, lines);
+        } else {
+            println!("✗ {:?} @ {} -> {:?} (expected {:?})", input, width, lines, expected);
+        }
+    }
+}
+
+pub fn test_announcers() {
+    println!("\nTesting announcers...");
+    
+    let test_cases = vec![
+        ("say", "say 5"),
+        ("espeak-ng", "espeak-ng 5"),
+        ("spd-say", "spd-say --wait 5"),
+        ("piper --text {} --quiet", "piper --text 5 --quiet"),
+        ("notify-send Countdown", "notify-send Countdown 5"),
+    ];
+    
+    for (voice_cmd, expected) in test_cases {
+        let Some(announcer) = from_voice_cmd(voice_cmd) else {
+            println!("✗ {:?} -> no announcer", voice_cmd);
+            continue;
+        };
+        let command = announcer.command("5");
+        let line = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if line == expected {
+            println!("✓ {:?} -> {} (correct)", voice_cmd, line);
+        } else {
+            println!("✗ {:?} -> {} (expected {})", voice_cmd, line, expected);
+        }
+    }
+    
+    if from_voice_cmd("   ").is_none() {
+        println!("✓ empty voice command -> None (correct)");
+    } else {
+        println!("✗ empty voice command -> announcer (expected None)");
+    }
+}
+
+pub fn test_announcement_schedule() {
+    println!("\nTesting announcement schedule...");
+    
+    let schedule = match Schedule::parse("10m,5m,1m30s,30s,10..1") {
+        Ok(schedule) => schedule,
+        Err(e) => {
+            println!("✗ parse -> Error: {}", e);
+            return;
+        }
+    };
+    
+    let test_cases = vec![
+        (600, TimerMode::CountDown, Some("ten minutes remaining")),
+        (90, TimerMode::CountDown, Some("one minute thirty seconds remaining")),
+        (30, TimerMode::CountDown, Some("thirty seconds remaining")),
+        (7, TimerMode::CountDown, Some("seven")),
+        (45, TimerMode::CountDown, None),
+        (0, TimerMode::CountDown, None),
+        (300, TimerMode::CountUp, Some("five minutes elapsed")),
+    ];
+    
+    for (seconds, mode, expected) in test_cases {
+        let text = schedule.announcement(Duration::from_secs(seconds), mode);
+        if text.as_deref() == expected {
+            println!("✓ {}s {:?} -> {:?} (correct)", seconds, mode, text);
+        } else {
+            println!("✗ {}s {:?} -> {:?} (expected {:?})", seconds, mode, text, expected);
+        }
+    }
+    
+    let default_cases = vec![
+        (TimerMode::CountDown, 3, Some("three")),
+        (TimerMode::CountDown, 11, None),
+        (TimerMode::CountUp, 3, None),
+    ];
+    for (mode, seconds, expected) in default_cases {
+        let text = Schedule::default_for(mode).announcement(Duration::from_secs(seconds), mode);
+        if text.as_deref() == expected {
+            println!("✓ default {:?} at {}s -> {:?} (correct)", mode, seconds, text);
+        } else {
+            println!("✗ default {:?} at {}s -> {:?} (expected {:?})", mode, seconds, text, expected);
+        }
+    }
+    
+    match Schedule::parse("0..100000h") {
+        Ok(long) if long.announcement(Duration::from_secs(359_999_999), TimerMode::CountDown).is_some() => {
+            println!("✓ 0..100000h -> counted without listing every second (correct)");
+        }
+        other => println!("✗ 0..100000h -> {:?}", other.map(|_| "no announcement")),
+    }
+    
+    if Schedule::default_for(TimerMode::CountUp).is_empty() && !Schedule::default_for(TimerMode::CountDown).is_empty() {
+        println!("✓ default schedule empty only when counting up (correct)");
+    } else {
+        println!("✗ default schedule emptiness wrong");
+    }
+    
+    for input in ["", "10x", "5..y"] {
+        match Schedule::parse(input) {
+            Ok(_) => println!("✗ {:?} -> parsed (should have been an error)", input),
+            Err(e) => println!("✓ {:?} -> Error: {} (correct)", input, e),
+        }
+    }
+}
+
+struct FakeNotify {
+    name: &'static str,
+    program: &'static str,
+}
+
+impl NotifyCommand for FakeNotify {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    
+    fn command(&self, _summary: &str, _body: &str) -> std::process::Command {
+        std::process::Command::new(self.program)
+    }
+}
+
+pub fn test_notifications() {
+    println!("\nTesting notifications...");
+    
+    let command = DBus.command("Tea \"time\"", "5");
+    let args: Vec<_> = command.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect();
+    let expected = ["\"Tea \\\"time\\\"\"", "\"5\""];
+    if args.len() >= 8 && args[args.len() - 5..args.len() - 3] == expected {
+        println!("✓ gdbus arguments quoted as strings (correct)");
+    } else {
+        println!("✗ gdbus arguments -> {:?} (expected {:?} before actions)", args, expected);
+    }
+    
+    let notifier = Notifier::new(vec![
+        Box::new(FakeNotify { name: "missing", program: "countdown-tui-no-such-program" }),
+        Box::new(FakeNotify { name: "failing", program: "false" }),
+        Box::new(FakeNotify { name: "working", program: "true" }),
+    ]);
+    match notifier.send_blocking("Countdown", "Time's up") {
+        Some("working") => println!("✓ fallback -> working (correct)"),
+        other => println!("✗ fallback -> {:?} (expected working)", other),
+    }
+
+    // A stand-in gdbus first on PATH records the call the D-Bus sender makes
+    let dir = std::env::temp_dir().join(format!("countdown-tui-gdbus-{}", std::process::id()));
+    let log = dir.join("calls");
+    let script = format!("#!/bin/sh\nprintf '%s\\n' \"$DBUS_SESSION_BUS_ADDRESS\" \"$@\" > '{}'\n", log.display());
+    let installed = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(dir.join("gdbus"), script));
+    #[cfg(unix)]
+    let installed = installed.and_then(|_| {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir.join("gdbus"), std::fs::Permissions::from_mode(0o755))
+    });
+    if let Err(e) = installed {
+        println!("✗ stand-in gdbus -> Error: {}", e);
+        return;
+    }
+
+    let saved_path = std::env::var_os("PATH");
+    let saved_bus = std::env::var_os("DBUS_SESSION_BUS_ADDRESS");
+    let mut path = std::ffi::OsString::from(&dir);
+    if let Some(saved) = &saved_path {
+        path.push(":");
+        path.push(saved);
+    }
+    std::env::set_var("PATH", path);
+    std::env::set_var("DBUS_SESSION_BUS_ADDRESS", "unix:path=/tmp/countdown-tui-test-bus");
+    let sent = Notifier::detect().and_then(|notifier| notifier.send_blocking("Tea", "Time's up"));
+    match &saved_path {
+        Some(saved) => std::env::set_var("PATH", saved),
+        None => std::env::remove_var("PATH"),
+    }
+    match &saved_bus {
+        Some(saved) => std::env::set_var("DBUS_SESSION_BUS_ADDRESS", saved),
+        None => std::env::remove_var("DBUS_SESSION_BUS_ADDRESS"),
+    }
+
+    let call: Vec<String> = std::fs::read_to_string(&log).unwrap_or_default().lines().map(str::to_string).collect();
+    let _ = std::fs::remove_dir_all(&dir);
+    let expected = [
+        "unix:path=/tmp/countdown-tui-test-bus",
+        "call",
+        "--session",
+        "--timeout",
+        "3",
+        "--dest",
+        "org.freedesktop.Notifications",
+        "--object-path",
+        "/org/freedesktop/Notifications",
+        "--method",
+        "org.freedesktop.Notifications.Notify",
+        "\"countdown-tui\"",
+        "uint32 0",
+        "\"\"",
+        "\"Tea\"",
+        "\"Time's up\"",
+        "@as []",
+        "@a{sv} {}",
+        "int32 -1",
+    ];
+    if sent == Some("gdbus") && call == expected {
+        println!("✓ D-Bus sender -> Notify call on the session bus (correct)");
+    } else {
+        println!("✗ D-Bus sender -> sent by {:?} with {:?} (expected gdbus with {:?})", sent, call, expected);
+    }
+}
+
+pub fn test_terminal_notifications() {
+    println!("\nTesting terminal notifications...");
+    
+    let test_cases = vec![
+        (osc::notify9("Tea: Time's up"), "\x1b]9;Tea: Time's up\x07"),
+        (osc::notify777("Tea; hot", "Time's up\x07"), "\x1b]777;notify;Tea, hot;Time's up\x07"),
+        (osc::passthrough("\x1b]9;hi\x07"), "\x1bPtmux;\x1b\x1b]9;hi\x07\x1b\\"),
+        (osc::window_title("12:34 – Coffee\x1b Break"), "\x1b]0;12:34 – Coffee Break\x07"),
+        (osc::progress(osc::ProgressState::Normal, 42), "\x1b]9;4;1;42\x07"),
+        (osc::progress(osc::ProgressState::Paused, 250), "\x1b]9;4;4;100\x07"),
+        (osc::progress(osc::ProgressState::Hidden, 0), "\x1b]9;4;0;0\x07"),
+    ];
+    
+    for (sequence, expected) in test_cases {
+        if sequence == expected {
+            println!("✓ {:?} (correct)", sequence);
+        } else {
+            println!("✗ {:?} (expected {:?})", sequence, expected);
+        }
+    }
+}
+
+pub fn test_threshold_hooks() {
+    println!("\nTesting threshold hooks...");
+    
+    let hooks = Hooks {
+        thresholds: vec![
+            ThresholdHook { at: Duration::from_secs(60), command: "one".to_string() },
+            ThresholdHook { at: Duration::from_secs(10), command: "two".to_string() },
+        ],
+        ..Hooks::default()
+    };
+    
+    let test_cases = vec![
+        (61, 60, vec!["one"]),
+        (60, 59, vec![]),
+        (300, 5, vec!["one", "two"]),
+        (5, 4, vec![]),
+    ];
+    
+    for (before, now, expected) in test_cases {
+        let fired: Vec<_> = hooks
+            .crossed(Duration::from_secs(before), Duration::from_secs(now))
+            .map(|hook| hook.command.as_str())
+            .collect();
+        if fired == expected {
+            println!("✓ {}s -> {}s fires {:?} (correct)", before, now, fired);
+        } else {
+            println!("✗ {}s -> {}s fires {:?} (expected {:?})", before, now, fired, expected);
+        }
+    }
+}
+
+pub fn test_summary() {
+    println!("\nTesting exit codes and summary...");
+    
+    for stop in [EndReason::Cancelled, EndReason::Signal(15)] {
+        let reason = stop.after_deadline();
+        if reason == EndReason::Overtime && !reason.is_cancelled() {
+            println!("✓ {:?} on the time's up screen -> {:?} (correct)", stop, reason);
+        } else {
+            println!("✗ {:?} on the time's up screen -> {:?} (expected Overtime)", stop, reason);
+        }
+    }
+    
+    let test_cases = vec![
+        (EndReason::Finished, 0),
+        (EndReason::Cancelled, 1),
+        (EndReason::Overtime, 3),
+        (EndReason::Signal(15), 143),
+    ];
+    
+    for (reason, expected) in test_cases {
+        if reason.exit_code() == expected {
+            println!("✓ {:?} exits with {} (correct)", reason, expected);
+        } else {
+            println!("✗ {:?} exits with {} (expected {})", reason, reason.exit_code(), expected);
+        }
+    }
+    
+    let mut state = CountdownState::new(Duration::from_secs(90), TimerMode::CountDown);
+    for _ in 0..30 {
+        state.tick();
+    }
+    state.lap();
+    state.pause();
+    state.resume();
+    state.tick();
+    state.lap();
+    
+    let json = Summary::new(&state, EndReason::Cancelled, Duration::ZERO).format(SummaryFormat::Json);
+    let expected = r#"{"reason":"cancelled","exit_code":1,"total":90,"elapsed":31,"overtime":0,"paused":0,"pause_count":1,"laps":[30,31]}"#;
+    if json == expected {
+        println!("✓ {} (correct)", json);
+    } else {
+        println!("✗ {} (expected {})", json, expected);
+    }
+}
+
+pub fn test_control_protocol() {
+    println!("\nTesting control protocol...");
+    
+    let test_cases = vec![
+        (r#"{"command":"pause"}"#, Ok(Control::Pause)),
+        (r#"{"command":"add","duration":"5m"}"#, Ok(Control::AddTime(Duration::from_secs(300)))),
+        (r#"{"command":"title","text":"Tea"}"#, Ok(Control::SetTitle("Tea".to_string()))),
+        (r#"{"command":"stop"}"#, Ok(Control::Stop(EndReason::Cancelled))),
+        (r#"{"command":"add","duration":"soon"}"#, Err(())),
+        (r#"{"command":"explode"}"#, Err(())),
+    ];
+    
+    for (line, expected) in test_cases {
+        let control = serde_json::from_str::<Request>(line)
+            .map_err(|e| e.to_string())
+            .and_then(Request::into_control);
+        if control.clone().map_err(|_| ()) == expected {
+            println!("✓ {} -> {:?} (correct)", line, control);
+        } else {
+            println!("✗ {} -> {:?} (expected {:?})", line, control, expected);
+        }
+    }
+    
+    let mut state = CountdownState::new(Duration::from_secs(60), TimerMode::CountDown);
+    state.tick();
+    state.pause();
+    let json = serde_json::to_string(&Status::new(&state, Some("Tea"), None)).unwrap_or_default();
+    let expected = r#"{"state":"paused","mode":"down","title":"Tea","total":60,"elapsed":1,"remaining":59,"paused":0,"pause_count":1,"laps":[]}"#;
+    if json == expected {
+        println!("✓ {} (correct)", json);
+    } else {
+        println!("✗ {} (expected {})", json, expected);
+    }
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("countdown-tui-sockets-{}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+        for (mode, private) in [(0o700, true), (0o755, false), (0o777, false)] {
+            let _ = std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(mode));
+            let checked = crate::socket::check_private(&dir);
+            if checked.is_ok() == private {
+                println!("✓ socket directory {:o} -> {:?} (correct)", mode, checked.map_err(|e| e.to_string()));
+            } else {
+                println!("✗ socket directory {:o} -> {:?} (expected private = {})", mode, checked.map_err(|e| e.to_string()), private);
+            }
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
+pub fn test_http_api() {
+    println!("\nTesting HTTP API...");
+    
+    let test_cases = vec![
+        ("5m", "5m"),
+        (" 90s\n", "90s"),
+        (r#"{"duration":"1h"}"#, "1h"),
+    ];
+    
+    for (body, expected) in test_cases {
+        let duration = duration_from_body(body);
+        if duration == expected {
+            println!("✓ POST /add {:?} adds {} (correct)", body, duration);
+        } else {
+            println!("✗ POST /add {:?} adds {} (expected {})", body, duration, expected);
+        }
+    }
+    
+    let origin_cases = vec![
+        (None, Some("127.0.0.1:7777"), None, true),
+        (Some("http://127.0.0.1:7777"), Some("127.0.0.1:7777"), None, true),
+        (Some("https://evil.example"), Some("127.0.0.1:7777"), None, false),
+        (Some("null"), Some("127.0.0.1:7777"), None, false),
+        (Some("http://localhost:8080"), Some("127.0.0.1:7777"), Some("http://localhost:8080/"), true),
+        (Some("https://evil.example"), Some("127.0.0.1:7777"), Some("http://localhost:8080"), false),
+    ];
+    for (origin, host, allow, expected) in origin_cases {
+        let allowed = origin_allowed(origin, host, allow);
+        if allowed == expected {
+            println!("✓ Origin {:?} with --http-allow-origin {:?} -> allowed = {} (correct)", origin, allow, allowed);
+        } else {
+            println!("✗ Origin {:?} with --http-allow-origin {:?} -> allowed = {} (expected {})", origin, allow, allowed, expected);
+        }
+    }
+    
+    let loopback: std::net::SocketAddr = "127.0.0.1:17777".parse().unwrap();
+    let everywhere: std::net::SocketAddr = "0.0.0.0:17777".parse().unwrap();
+    let host_cases = vec![
+        (Some("127.0.0.1:17777"), loopback, true),
+        (Some("localhost:17777"), loopback, true),
+        (Some("[::1]:17777"), loopback, true),
+        (None, loopback, true),
+        // DNS rebinding: a page from evil.example that now resolves to us
+        (Some("evil.example:17777"), loopback, false),
+        (Some("127.0.0.1:80"), loopback, false),
+        (Some("127.0.0.1"), loopback, false),
+        (Some("192.168.1.20:17777"), everywhere, true),
+        (Some("evil.example:17777"), everywhere, false),
+    ];
+    for (host, addr, expected) in host_cases {
+        let allowed = host_allowed(host, addr);
+        if allowed == expected {
+            println!("✓ Host {:?} on {} -> allowed = {} (correct)", host, addr, allowed);
+        } else {
+            println!("✗ Host {:?} on {} -> allowed = {} (expected {})", host, addr, allowed, expected);
+        }
+    }
+}
+
+pub fn test_headless_lines() {
+    println!("\nTesting headless status lines...");
+    
+    let mut printer = LinePrinter::with_writer(Vec::new(), Duration::from_secs(2), LineStyle::Log);
+    let mut state = CountdownState::new(Duration::from_secs(5), TimerMode::CountDown);
+    let title = Some("Tea");
+    
+    let _ = printer.running(&state, title);
+    for _ in 0..3 {
+        state.tick();
+        let _ = printer.running(&state, title);
+    }
+    state.pause();
+    let _ = printer.paused(&state, title);
+    let _ = printer.paused(&state, title);
+    state.resume();
+    let _ = printer.running(&state, title);
+    state.finish();
+    let _ = printer.ended(&state, title, EndReason::Finished);
+    
+    let output = String::from_utf8_lossy(printer.writer()).into_owned();
+    let expected = "Tea: 00:05 remaining\nTea: 00:03 remaining\nTea: paused at 00:02\nTea: 00:02 remaining\nTea: time's up\n";
+    if output == expected {
+        println!("✓ {:?} (correct)", output);
+    } else {
+        println!("✗ {:?} (expected {:?})", output, expected);
+    }    
+    let mut printer = LinePrinter::with_writer(Vec::new(), Duration::from_secs(1), LineStyle::Compact);
+    let mut state = CountdownState::new(Duration::from_secs(2), TimerMode::CountDown);
+    let _ = printer.running(&state, title);
+    state.tick();
+    let _ = printer.running(&state, title);
+    state.pause();
+    let _ = printer.paused(&state, title);
+    state.resume();
+    state.finish();
+    let _ = printer.ended(&state, None, EndReason::Finished);
+    
+    let output = String::from_utf8_lossy(printer.writer()).into_owned();
+    let expected = "⏱ 00:02 Tea\n⏱ 00:01 Tea\n⏸ 00:01 Tea\n⏰ 00:00\n";
+    if output == expected {
+        println!("✓ {:?} (correct)", output);
+    } else {
+        println!("✗ {:?} (expected {:?})", output, expected);
+    }
+    
+    // As read back from a running countdown by `countdown-tui status`
+    let test_cases = vec![
+        (r#"{"state":"running","mode":"up","title":"Standup","total":0,"elapsed":754,"remaining":0,"paused":0,"pause_count":0,"laps":[]}"#, "⏱ 12:34 Standup"),
+        (r#"{"state":"signal","mode":"down","total":60,"elapsed":20,"remaining":40,"paused":0,"pause_count":0,"laps":[]}"#, "⏹ 00:40"),
+    ];
+    
+    for (json, expected) in test_cases {
+        let line = serde_json::from_str::<Status>(json).map(|status| status_line(&status));
+        match line {
+            Ok(line) if line == expected => println!("✓ {} (correct)", line),
+            Ok(line) => println!("✗ {} (expected {})", line, expected),
+            Err(e) => println!("✗ {} -> Error: {}", json, e),
+        }
+    }
+}
+
+pub fn test_event_stream() {
+    println!("\nTesting event stream...");
+    
+    let test_cases = vec![
+        ("json", Ok(None)),
+        ("json=/tmp/events.ndjson", Ok(Some("/tmp/events.ndjson"))),
+        ("yaml", Err(())),
+        ("json=", Err(())),
+    ];
+    
+    for (input, expected) in test_cases {
+        let target = EventTarget::parse(input);
+        let path = target.as_ref().map(|target| target.path.as_ref().and_then(|path| path.to_str())).map_err(|_| ());
+        if path == expected {
+            println!("✓ --events {} -> {:?} (correct)", input, target);
+        } else {
+            println!("✗ --events {} -> {:?} (expected {:?})", input, target, expected);
+        }
+    }
+    
+    let path = std::env::temp_dir().join(format!("countdown-tui-events-{}.ndjson", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let Ok(log) = EventLog::open(&EventTarget { path: Some(path.clone()) }) else {
+        println!("✗ could not open {}", path.display());
+        return;
+    };
+    
+    let mut state = CountdownState::new(Duration::from_secs(60), TimerMode::CountDown);
+    log.emit(&state, Event::Started);
+    state.tick();
+    log.emit(&state, Event::Tick);
+    state.add_time(Duration::from_secs(30));
+    log.emit(&state, Event::adjusted(Duration::from_secs(30)));
+    log.emit(&state, Event::cancelled(EndReason::Signal(15)));
+    
+    let expected = vec![
+        r#"{"event":"started","total":60,"elapsed":0,"remaining":60}"#,
+        r#"{"event":"tick","total":60,"elapsed":1,"remaining":59}"#,
+        r#"{"event":"adjusted","added":30,"total":90,"elapsed":1,"remaining":89}"#,
+        r#"{"event":"cancelled","reason":"signal","signal":15,"total":90,"elapsed":1,"remaining":89}"#,
+    ];
+    let contents = std::fs::read_to_string(&path).unwrap_or_default();
+    let _ = std::fs::remove_file(&path);
+    
+    for (line, expected) in contents.lines().zip(expected) {
+        // Drop the timestamp, which differs on every run
+        let mut event: serde_json::Value = serde_json::from_str(line).unwrap_or_default();
+        let has_time = event.as_object_mut().and_then(|event| event.remove("time")).is_some();
+        let expected_event: serde_json::Value = serde_json::from_str(expected).unwrap_or_default();
+        if has_time && event == expected_event {
+            println!("✓ {} (correct)", line);
+        } else {
+            println!("✗ {} (expected {} with a time)", line, expected);
+        }
+    }
+}
+
+pub fn test_digit_fonts() {
+    println!("\nTesting digit font fallback...");
+    
+    let time = Duration::from_secs(90);
+    let test_cases = vec![
+        (80, 24, DigitFont::Big),
+        (80, 3, DigitFont::Small),
+        (20, 24, DigitFont::Small),
+        (80, 1, DigitFont::Plain),
+        (10, 24, DigitFont::Plain),
+    ];
+    
+    for (width, height, expected) in test_cases {
+        let font = DigitFont::fit(time, width, height);
+        if font == expected {
+            println!("✓ {}x{} -> {:?} (correct)", width, height, font);
+        } else {
+            println!("✗ {}x{} -> {:?} (expected {:?})", width, height, font, expected);
+        }
+    }
+    
+    for (width, height) in [(40, 3), (30, 1)] {
+        let mut display = Display::with_backend(MemoryBackend::new(width, height));
+        if let Err(e) = render_countdown(&mut display, time, &None, DigitStyle::default(), None) {
+            println!("✗ render {}x{} -> Error: {}", width, height, e);
+            continue;
+        }
+        let lines = display.backend().screen().text_lines();
+        let expected = DigitFont::fit(time, width as usize, height as usize).lines(time);
+        if lines.iter().any(|line| line.trim() == expected[0].trim()) {
+            println!("✓ {}x{} screen -> digits drawn (correct)", width, height);
+        } else {
+            println!("✗ {}x{} screen -> digits missing", width, height);
+        }
+        for line in &lines {
+            println!("  |{}", line);
+        }
+    }
+}
+
+pub fn test_start_screen() {
+    println!("\nTesting start screen...");
+    
+    let mut history = History::default();
+    history.add(Entry { duration: "10m".to_string(), title: None });
+    history.add(Entry { duration: "25m".to_string(), title: Some("Focus".to_string()) });
+    history.add(Entry { duration: "10m".to_string(), title: None });
+    let durations: Vec<_> = history.entries.iter().map(|entry| entry.duration.as_str()).collect();
+    if durations == ["10m", "25m"] {
+        println!("✓ history {:?} (correct)", durations);
+    } else {
+        println!("✗ history {:?} (expected [\"10m\", \"25m\"])", durations);
+    }
+    
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    let type_text = |screen: &mut StartScreen, text: &str| {
+        for ch in text.chars() {
+            screen.handle_key(key(KeyCode::Char(ch)));
+        }
+    };
+    
+    let mut screen = StartScreen::new(&history, Some("Tea"));
+    let enter_empty = screen.handle_key(key(KeyCode::Enter));
+    type_text(&mut screen, "5x");
+    let invalid = screen.validation().is_some_and(|check| check.is_err());
+    let enter_invalid = screen.handle_key(key(KeyCode::Enter));
+    screen.handle_key(key(KeyCode::Backspace));
+    type_text(&mut screen, "m");
+    let valid = screen.validation().and_then(|check| check.ok());
+    let started = screen.handle_key(key(KeyCode::Enter));
+    let expected = Some(StartAction::Start(Entry { duration: "5m".to_string(), title: Some("Tea".to_string()) }));
+    if enter_empty.is_none() && invalid && enter_invalid.is_none() && valid == Some(Duration::from_secs(300)) && started == expected {
+        println!("✓ typed 5x, fixed to 5m -> {:?} (correct)", started);
+    } else {
+        println!("✗ typed 5x, fixed to 5m -> {:?} (expected {:?})", started, expected);
+    }
+    
+    let test_cases = vec![
+        (vec![KeyCode::Down, KeyCode::Down, KeyCode::Right, KeyCode::Right], Entry { duration: "10m".to_string(), title: Some("Tea".to_string()) }),
+        (vec![KeyCode::Tab, KeyCode::Tab, KeyCode::Tab, KeyCode::Down], Entry { duration: "25m".to_string(), title: Some("Focus".to_string()) }),
+    ];
+    
+    for (keys, expected) in test_cases {
+        let mut screen = StartScreen::new(&history, Some("Tea"));
+        for code in &keys {
+            screen.handle_key(key(*code));
+        }
+        let started = screen.handle_key(key(KeyCode::Enter));
+        if started == Some(StartAction::Start(expected.clone())) {
+            println!("✓ {:?} -> {:?} (correct)", keys, expected);
+        } else {
+            println!("✗ {:?} -> {:?} (expected {:?})", keys, started, expected);
+        }
+    }
+    
+    let mut screen = StartScreen::new(&history, None);
+    if screen.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)) == Some(StartAction::Cancel) {
+        println!("✓ Ctrl+C -> cancel (correct)");
+    } else {
+        println!("✗ Ctrl+C does not cancel");
+    }
+    
+    type_text(&mut screen, "25m");
+    let mut display = Display::with_backend(MemoryBackend::new(60, 20));
+    if let Err(e) = render_start(&mut display, &screen) {
+        println!("✗ render start screen -> Error: {}", e);
+        return;
+    }
+    let lines = display.backend().screen().text_lines();
+    if lines.iter().any(|line| line.contains("✓ 25:00")) && lines.iter().any(|line| line.contains("Focus")) {
+        println!("✓ start screen -> check and recent countdowns (correct)");
+    } else {
+        println!("✗ start screen -> check or recent countdowns missing");
+    }
+    for line in &lines {
+        println!("  |{}", line);
+    }
+}
+
+pub fn test_keymap() {
+    println!("\nTesting keymap...");
+    
+    let keymap = Keymap::default();
+    let test_cases = vec![
+        (KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE), Some(Action::TogglePause)),
+        (KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE), Some(Action::TogglePause)),
+        (KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT), Some(Action::Help)),
+        (KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE), Some(Action::Quit)),
+        (KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), Some(Action::Quit)),
+        (KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL), Some(Action::Quit)),
+        (KeyEvent::new(KeyCode::Char('+'), KeyModifiers::SHIFT), Some(Action::AddTime)),
+        (KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE), None),
+        (KeyEvent::new(KeyCode::Char('L'), KeyModifiers::SHIFT), None),
+    ];
+    
+    for (key, expected) in test_cases {
+        let action = keymap.action(&key);
+        let name = Key { code: key.code, modifiers: key.modifiers }.name();
+        if action == expected {
+            println!("✓ {} -> {:?} (correct)", name, action);
+        } else {
+            println!("✗ {} -> {:?} (expected {:?})", name, action, expected);
+        }
+    }
+    
+    let test_cases = vec![
+        ("q", Ok(Key::plain(KeyCode::Char('q')))),
+        ("+", Ok(Key::plain(KeyCode::Char('+')))),
+        ("Space", Ok(Key::plain(KeyCode::Char(' ')))),
+        ("ctrl+X", Ok(Key::ctrl('x'))),
+        ("f5", Ok(Key::plain(KeyCode::F(5)))),
+        ("hyper+q", Err(())),
+    ];
+    
+    for (input, expected) in test_cases {
+        let key = Key::parse(input);
+        if key.as_ref().ok() == expected.as_ref().ok() && key.is_ok() == expected.is_ok() {
+            println!("✓ key {:?} -> {:?} (correct)", input, key);
+        } else {
+            println!("✗ key {:?} -> {:?} (expected {:?})", input, key, expected);
+        }
+    }
+    
+    let config = Config::parse("[keys]\nq = \"none\"\nx = \"quit\"\nn = \"add\"\n");
+    let keymap = config.and_then(|config| Keymap::with_overrides(&config.keys));
+    let lookup = |keymap: &Keymap, ch| keymap.action(&KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+    match &keymap {
+        Ok(keymap) if lookup(keymap, 'q').is_none() && lookup(keymap, 'x') == Some(Action::Quit) && lookup(keymap, 'n') == Some(Action::AddTime) => {
+            println!("✓ config unbinds q and binds x and n (correct)");
+        }
+        _ => println!("✗ config keys -> {:?}", keymap),
+    }
+    for toml in ["[keys]\nq = \"explode\"\n", "[keyz]\nq = \"quit\"\n"] {
+        let keymap = Config::parse(toml).and_then(|config| Keymap::with_overrides(&config.keys));
+        match keymap {
+            Err(e) => println!("✓ {:?} -> Error: {} (correct)", toml, e.trim_end()),
+            Ok(_) => println!("✗ {:?} was accepted", toml),
+        }
+    }
+    
+    let keymap = Keymap::default();
+    let footer = keymap.footer();
+    let help = keymap.help();
+    let quit_keys = help.iter().find(|(_, description)| *description == Action::Quit.description());
+    if quit_keys.is_some_and(|(keys, _)| keys == "q, Esc, Ctrl+C") && help.len() == Action::ALL.len() {
+        println!("✓ help lists {} actions (correct)", help.len());
+    } else {
+        println!("✗ help {:?}", help);
+    }
+    
+    let mut display = Display::with_backend(MemoryBackend::new(60, 20));
+    display.set_footer(Some(footer));
+    display.set_help(Some(help));
+    if let Err(e) = render_countdown(&mut display, Duration::from_secs(90), &None, DigitStyle::default(), None) {
+        println!("✗ render with help -> Error: {}", e);
+        return;
+    }
+    let lines = display.backend().screen().text_lines();
+    let has_help = lines.iter().any(|line| line.contains("q, Esc, Ctrl+C   Stop the countdown"));
+    // Only the hints that fit in 60 columns
+    let expected = "Space pause  ? help  q quit  l lap  r reset  + add";
+    if has_help && lines.last().is_some_and(|line| line.trim() == expected) {
+        println!("✓ help box and footer on screen (correct)");
+    } else {
+        println!("✗ help box or footer missing");
+    }
+    for line in &lines {
+        println!("  |{}", line);
+    }
+}
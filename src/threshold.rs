@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+/// How close the countdown is to its deadline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Urgency {
+    Normal,
+    Warning,
+    Critical,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Thresholds {
+    pub warn: Option<Duration>,
+    pub critical: Option<Duration>,
+    pub blink: bool,
+}
+
+/// Seconds before the deadline in which the digits pulse when blinking is enabled.
+const BLINK_WINDOW: Duration = Duration::from_secs(10);
+
+impl Thresholds {
+    pub fn urgency(&self, time_left: Duration) -> Urgency {
+        if self.critical.is_some_and(|critical| time_left <= critical) {
+            Urgency::Critical
+        } else if self.warn.is_some_and(|warn| time_left <= warn) {
+            Urgency::Warning
+        } else {
+            Urgency::Normal
+        }
+    }
+
+    pub fn should_blink(&self, time_left: Duration) -> bool {
+        self.blink && time_left <= BLINK_WINDOW
+    }
+}
@@ -30,7 +30,7 @@ pub fn parse_duration_or_time(input: &str) -> Result<Duration, ParseError> {
     Err(ParseError::InvalidFormat(input.to_string()))
 }
 
-fn parse_duration_string(input: &str) -> Result<Duration, ParseError> {
+pub fn parse_duration_string(input: &str) -> Result<Duration, ParseError> {
     let input = input.trim();
     
     if input.is_empty() {
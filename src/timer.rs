@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Copy, Debug)]
 pub enum TimerMode {
@@ -12,6 +12,12 @@ pub struct CountdownState {
     remaining_duration: Duration,
     mode: TimerMode,
     paused: bool,
+    paused_since: Option<Instant>,
+    /// Time spent in pauses that have already ended.
+    paused_total: Duration,
+    pause_count: u32,
+    /// Elapsed time at each lap.
+    laps: Vec<Duration>,
 }
 
 impl CountdownState {
@@ -21,6 +27,10 @@ impl CountdownState {
             remaining_duration: duration,
             mode,
             paused: false,
+            paused_since: None,
+            paused_total: Duration::ZERO,
+            pause_count: 0,
+            laps: Vec::new(),
         }
     }
     
@@ -37,22 +47,93 @@ impl CountdownState {
         }
     }
     
+    /// Push the deadline back by `extra`. When counting up the target moves,
+    /// so the elapsed time stays as it is.
+    pub fn add_time(&mut self, extra: Duration) {
+        self.total_duration += extra;
+        self.remaining_duration += extra;
+    }
+    
+    /// Bring the deadline forward by `less`, but not past the present.
+    /// Returns how much was actually taken off.
+    pub fn subtract_time(&mut self, less: Duration) -> Duration {
+        let removed = less.min(self.remaining_duration);
+        self.total_duration -= removed;
+        self.remaining_duration -= removed;
+        removed
+    }
+    
+    /// Start over from the full duration, dropping the laps. A paused
+    /// countdown stays paused.
+    pub fn reset(&mut self) {
+        self.remaining_duration = self.total_duration;
+        self.laps.clear();
+    }
+    
+    /// Jump to the end, for when the deadline fires between ticks.
+    pub fn finish(&mut self) {
+        self.remaining_duration = Duration::ZERO;
+    }
+    
     pub fn pause(&mut self) {
         if !self.paused {
             self.paused = true;
+            self.paused_since = Some(Instant::now());
+            self.pause_count += 1;
         }
     }
     
     pub fn resume(&mut self) {
         if self.paused {
             self.paused = false;
+            self.paused_total += self.paused_for();
+            self.paused_since = None;
         }
     }
     
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+    
     pub fn is_paused(&self) -> bool {
         self.paused
     }
     
+    /// How long the current pause has lasted, zero when running.
+    pub fn paused_for(&self) -> Duration {
+        self.paused_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+    
+    /// Time spent paused so far, including the current pause.
+    pub fn paused_total(&self) -> Duration {
+        self.paused_total + self.paused_for()
+    }
+    
+    pub fn pause_count(&self) -> u32 {
+        self.pause_count
+    }
+    
+    /// Record a lap at the current elapsed time.
+    pub fn lap(&mut self) {
+        self.laps.push(self.elapsed());
+    }
+    
+    pub fn laps(&self) -> &[Duration] {
+        &self.laps
+    }
+    
+    pub fn total_duration(&self) -> Duration {
+        self.total_duration
+    }
+    
+    pub fn elapsed(&self) -> Duration {
+        self.total_duration - self.remaining_duration
+    }
+    
+    pub fn time_left(&self) -> Duration {
+        self.remaining_duration
+    }
+    
     pub fn display_duration(&self) -> Duration {
         match self.mode {
             TimerMode::CountDown => self.remaining_duration,
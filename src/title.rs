@@ -0,0 +1,92 @@
+use clap::ValueEnum;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Most lines a title may wrap onto before it is cut off with an ellipsis.
+pub const MAX_TITLE_LINES: usize = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TitlePosition {
+    Above,
+    #[default]
+    Below,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TitleAlign {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+#[derive(Clone, Debug)]
+pub struct Title {
+    pub text: String,
+    pub position: TitlePosition,
+    pub align: TitleAlign,
+}
+
+/// Number of terminal columns `text` occupies.
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Word-wrap `text` into lines at most `max_width` columns wide. Words wider
+/// than a line are broken wherever they hit the edge, which also covers
+/// scripts without spaces. Anything past `max_lines` is dropped and the last
+/// line ends with an ellipsis instead.
+pub fn wrap_title(text: &str, max_width: usize, max_lines: usize) -> Vec<String> {
+    let max_width = max_width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        for piece in split_to_width(word, max_width) {
+            if !line.is_empty() && display_width(&line) + 1 + display_width(&piece) > max_width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&piece);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            *last = with_ellipsis(last, max_width);
+        }
+    }
+    lines
+}
+
+fn split_to_width(word: &str, max_width: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    let mut width = 0;
+    for ch in word.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if width + ch_width > max_width && !piece.is_empty() {
+            pieces.push(std::mem::take(&mut piece));
+            width = 0;
+        }
+        piece.push(ch);
+        width += ch_width;
+    }
+    if !piece.is_empty() {
+        pieces.push(piece);
+    }
+    pieces
+}
+
+fn with_ellipsis(line: &str, max_width: usize) -> String {
+    let mut truncated = line.to_string();
+    while !truncated.is_empty() && display_width(&truncated) + 1 > max_width {
+        truncated.pop();
+    }
+    format!("{}…", truncated.trim_end())
+}
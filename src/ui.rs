@@ -1,112 +1,538 @@
-use crate::font::{get_font, get_paused_text, char_height, paused_text_height, paused_text_width};
-use crossterm::{
-    cursor, execute,
-    style::Print,
-    terminal::{Clear, ClearType, size},
-};
-use std::io::{stdout, Write};
+use crate::backend::{CrosstermBackend, RenderBackend};
+use crate::font::{get_font, char_height, render_text, render_text_scaled, text_height};
+use crate::frame::{CellStyle, Frame};
+use crate::start::{Focus, StartScreen, PRESETS};
+use crate::threshold::Urgency;
+use crate::title::{display_width, wrap_title, Title, TitleAlign, TitlePosition, MAX_TITLE_LINES};
+use crossterm::style::Color;
+use chrono::{DateTime, Local};
 use std::time::Duration;
 
-pub struct Display {
+pub struct Display<B: RenderBackend = CrosstermBackend> {
+    backend: B,
     width: u16,
+    /// Rows left for the screens, without the footer.
     height: u16,
+    screen_height: u16,
+    /// Key hints for a dim line along the bottom, as many as fit.
+    footer: Option<Vec<String>>,
+    /// Keys and what they do, shown in a box over everything else.
+    help: Option<Vec<(String, &'static str)>>,
+    /// What is currently on screen, or `None` when it is unknown and the
+    /// next frame has to be drawn in full.
+    previous: Option<Frame>,
 }
 
 impl Display {
     pub fn new() -> Self {
-        let (width, height) = size().unwrap_or((80, 24));
-        Self { width, height }
+        Self::with_backend(CrosstermBackend::new())
+    }
+    
+    /// Draw into `height` lines from the cursor down instead of the whole screen.
+    pub fn inline(height: u16) -> std::io::Result<Self> {
+        Ok(Self::with_backend(CrosstermBackend::inline(height)?))
+    }
+}
+
+impl<B: RenderBackend> Display<B> {
+    pub fn with_backend(backend: B) -> Self {
+        let mut display = Self {
+            backend,
+            width: 0,
+            height: 0,
+            screen_height: 0,
+            footer: None,
+            help: None,
+            previous: None,
+        };
+        display.update_size();
+        display
+    }
+    
+    pub fn backend(&self) -> &B {
+        &self.backend
     }
     
     pub fn update_size(&mut self) {
-        let (width, height) = size().unwrap_or((80, 24));
+        let (width, height) = self.backend.size();
         self.width = width;
-        self.height = height;
+        self.screen_height = height;
+        self.height = if self.footer.is_some() { height.saturating_sub(1).max(1) } else { height };
+    }
+    
+    pub fn set_footer(&mut self, footer: Option<Vec<String>>) {
+        self.footer = footer;
+        self.update_size();
+    }
+    
+    /// Show the help box with these keys over the next frames, or stop
+    /// showing it with `None`.
+    pub fn set_help(&mut self, help: Option<Vec<(String, &'static str)>>) {
+        self.help = help;
+    }
+    
+    pub fn showing_help(&self) -> bool {
+        self.help.is_some()
+    }
+    
+    /// Send an escape sequence that does not draw anything, like the bell.
+    pub fn write_escape(&mut self, sequence: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.backend.write_escape(sequence)?;
+        Ok(())
+    }
+    
+    /// Forget what is on screen so the next frame is drawn from scratch.
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+    
+    fn frame(&self) -> Frame {
+        Frame::new(self.width, self.screen_height)
+    }
+    
+    /// Draw only the parts of `frame` that changed since the last call,
+    /// after adding the footer and help box.
+    fn present(&mut self, mut frame: Frame) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(hints) = &self.footer {
+            let mut footer = String::new();
+            for hint in hints {
+                let line = if footer.is_empty() { hint.clone() } else { format!("{}  {}", footer, hint) };
+                if display_width(&line) > self.width as usize {
+                    break;
+                }
+                footer = line;
+            }
+            draw_centered(&mut frame, (self.screen_height as usize).saturating_sub(1), &footer, CellStyle { fg: None, dim: true });
+        }
+        if let Some(help) = &self.help {
+            draw_help(&mut frame, help);
+        }
+        
+        let runs = match &self.previous {
+            Some(previous) => frame.diff(previous),
+            None => {
+                // After a clear only the non-blank cells need drawing
+                self.backend.clear()?;
+                frame.diff(&self.frame())
+            }
+        };
+        self.backend.draw(&runs)?;
+        self.backend.flush()?;
+        
+        self.previous = Some(frame);
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DigitStyle {
+    pub urgency: Urgency,
+    pub dim: bool,
+}
+
+impl Default for DigitStyle {
+    fn default() -> Self {
+        Self { urgency: Urgency::Normal, dim: false }
     }
 }
 
-pub fn clear_screen() -> Result<(), Box<dyn std::error::Error>> {
-    execute!(stdout(), Clear(ClearType::All))?;
-    Ok(())
+#[derive(Clone, Copy, Debug)]
+pub struct Progress {
+    /// Elapsed share of the total duration, from 0.0 to 1.0
+    pub fraction: f64,
+    /// Wall-clock time at which the countdown will finish
+    pub eta: DateTime<Local>,
+}
+
+/// What the digits are drawn in: the big font when there is room, then the
+/// small text font, then plain text on a single line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigitFont {
+    Big,
+    Small,
+    Plain,
+}
+
+impl DigitFont {
+    /// The largest font that fits `duration` into `width` columns and
+    /// `height` rows.
+    pub fn fit(duration: Duration, width: usize, height: usize) -> Self {
+        [DigitFont::Big, DigitFont::Small]
+            .into_iter()
+            .find(|font| font.height() <= height && font.width(duration) <= width)
+            .unwrap_or(DigitFont::Plain)
+    }
+    
+    pub fn height(self) -> usize {
+        match self {
+            DigitFont::Big => char_height(),
+            DigitFont::Small => text_height(),
+            DigitFont::Plain => 1,
+        }
+    }
+    
+    /// Width of `duration` in this font, in columns.
+    pub fn width(self, duration: Duration) -> usize {
+        self.lines(duration).iter().map(|line| display_width(line)).max().unwrap_or(0)
+    }
+    
+    pub fn lines(self, duration: Duration) -> Vec<String> {
+        let time_string = format_duration(duration);
+        match self {
+            DigitFont::Big => {
+                // Join the glyphs row by row like original echo() function
+                let font = get_font();
+                let glyphs: Vec<_> = time_string.chars().filter_map(|ch| font.get(&ch)).collect();
+                (0..char_height())
+                    .map(|row| glyphs.iter().map(|char_map| char_map[row]).collect())
+                    .collect()
+            }
+            DigitFont::Small => render_text(&time_string).unwrap_or_else(|| vec![time_string]),
+            DigitFont::Plain => vec![time_string],
+        }
+    }
+}
+
+impl DigitStyle {
+    fn cell_style(self) -> CellStyle {
+        let fg = match self.urgency {
+            Urgency::Normal => None,
+            Urgency::Warning => Some(Color::Yellow),
+            Urgency::Critical => Some(Color::Red),
+        };
+        CellStyle { fg, dim: self.dim }
+    }
+}
+
+pub fn render_countdown<B: RenderBackend>(
+    display: &mut Display<B>,
+    duration: Duration,
+    title: &Option<Title>,
+    style: DigitStyle,
+    progress: Option<Progress>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    display.update_size();
+    let mut frame = display.frame();
+    
+    let progress_height = if progress.is_some() { 2 } else { 0 };
+    let title_room = (display.height as usize).saturating_sub(char_height() + 2 + progress_height);
+    let title_lines = wrapped_title(title, display.width, title_room);
+    let title_height = if title_lines.is_empty() { 0 } else { title_lines.len() + 1 };
+    let room = (display.height as usize).saturating_sub(title_height + progress_height);
+    let font = DigitFont::fit(duration, display.width as usize, room);
+    
+    // Centre the digits, moving them off centre only when what goes above
+    // or below them would not fit otherwise
+    let title_above = title.as_ref().is_some_and(|title| title.position == TitlePosition::Above);
+    let (above, mut below) = if title_above {
+        (title_height, progress_height)
+    } else {
+        (0, title_height + progress_height)
+    };
+    // Two blank lines under the digits, or one when short of room
+    let gap = if below > 0 && font.height() + below < display.height as usize { 2 } else { 1 };
+    if below > 0 {
+        below += gap - 1;
+    }
+    let top = ((display.height as usize).saturating_sub(font.height()) / 2)
+        .min((display.height as usize).saturating_sub(font.height() + below))
+        .max(above);
+    let mut below_y = draw_digits(&mut frame, top, duration, style, font) + gap;
+    
+    if let Some(progress) = progress {
+        let line = progress_line(progress, display.width as usize);
+        draw_centered(&mut frame, below_y, &line, CellStyle::default());
+        below_y += 2;
+    }
+    
+    if let Some(title) = title {
+        let title_y = match title.position {
+            TitlePosition::Above => top.saturating_sub(title_lines.len() + 1),
+            TitlePosition::Below => below_y,
+        };
+        draw_title(&mut frame, &title_lines, title_y, title.align, font.width(duration));
+    }
+    
+    display.present(frame)
 }
 
-pub fn render_countdown(
-    display: &mut Display,
+/// Draw the frozen digits dimmed, with the PAUSED banner and the length of
+/// the current pause below them.
+pub fn render_paused<B: RenderBackend>(
+    display: &mut Display<B>,
     duration: Duration,
-    title: &Option<String>,
+    title: &Option<Title>,
+    style: DigitStyle,
+    paused_for: Duration,
 ) -> Result<(), Box<dyn std::error::Error>> {
     display.update_size();
+    let mut frame = display.frame();
+    
+    let title_room = (display.height as usize).saturating_sub(char_height() + 1 + text_height() + 2);
+    let title_lines = wrapped_title(title, display.width, title_room);
+    let title_height = if title_lines.is_empty() { 0 } else { title_lines.len() + 1 };
+    let title_above = title.as_ref().is_some_and(|title| title.position == TitlePosition::Above);
+    
+    // Shrink the banner, then the digits, until the whole block fits
+    let height = display.height as usize;
+    let banner = render_text("PAUSED")
+        .filter(|banner| DigitFont::Plain.height() + 1 + banner.len() + 2 + title_height <= height)
+        .unwrap_or_else(|| vec!["PAUSED".to_string()]);
+    let room = height.saturating_sub(1 + banner.len() + 2 + title_height);
+    let font = DigitFont::fit(duration, display.width as usize, room);
+    
+    // Centre the whole block rather than just the digits so it fits on short terminals
+    let block_height = font.height() + 1 + banner.len() + 2 + title_height;
+    let mut top = height.saturating_sub(block_height) / 2;
+    if title_above {
+        top += title_height;
+    }
+    let digits_bottom = draw_digits(&mut frame, top, duration, DigitStyle { dim: true, ..style }, font);
+    
+    let banner_y = digits_bottom + 1;
+    for (row, line) in banner.iter().enumerate() {
+        draw_centered(&mut frame, banner_y + row, line, CellStyle::default());
+    }
+    
+    let paused_y = banner_y + banner.len() + 1;
+    let paused_line = format!("paused for {}", format_duration(paused_for));
+    draw_centered(&mut frame, paused_y, &paused_line, CellStyle { fg: None, dim: true });
+    
+    if let Some(title) = title {
+        let title_y = if title_above { top - title_height } else { paused_y + 2 };
+        draw_title(&mut frame, &title_lines, title_y, title.align, font.width(duration));
+    }
+    
+    display.present(frame)
+}
+
+/// The `--wait` end screen: a TIME'S UP banner that flashes with `flash`,
+/// the time since the deadline in the big digits and the title below.
+pub fn render_times_up<B: RenderBackend>(
+    display: &mut Display<B>,
+    overrun: Duration,
+    title: &Option<Title>,
+    flash: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    display.update_size();
+    let mut frame = display.frame();
+    
+    let title_room = (display.height as usize).saturating_sub(text_height() + 1 + char_height());
+    let title_lines = wrapped_title(title, display.width, title_room);
+    let title_height = if title_lines.is_empty() { 0 } else { title_lines.len() + 1 };
+    
+    // Twice the size of the paused banner when the terminal is big enough,
+    // leaving at least a line for the digits
+    let height = display.height as usize;
+    let max_width = (display.width as usize).saturating_sub(4);
+    let banner = [render_text_scaled("TIME'S UP", 2), render_text("TIME'S UP")]
+        .into_iter()
+        .flatten()
+        .find(|lines| {
+            lines.len() + 1 + DigitFont::Plain.height() + title_height <= height
+                && lines.iter().all(|line| display_width(line) <= max_width)
+        })
+        .unwrap_or_else(|| vec!["TIME'S UP".to_string()]);
+    let room = height.saturating_sub(banner.len() + 1 + title_height);
+    let font = DigitFont::fit(overrun, display.width as usize, room);
+    
+    let block_height = banner.len() + 1 + font.height() + title_height;
+    let top = height.saturating_sub(block_height) / 2;
+    
+    let banner_style = CellStyle { fg: Some(Color::Red), dim: !flash };
+    for (row, line) in banner.iter().enumerate() {
+        draw_centered(&mut frame, top + row, line, banner_style);
+    }
+    
+    let overrun_style = DigitStyle { urgency: Urgency::Critical, dim: false };
+    let digits_bottom = draw_digits(&mut frame, top + banner.len() + 1, overrun, overrun_style, font);
+    
+    if let Some(title) = title {
+        draw_title(&mut frame, &title_lines, digits_bottom + 1, title.align, font.width(overrun));
+    }
+    
+    display.present(frame)
+}
+
+/// The start screen: the duration and title fields with the duration
+/// checked as it is typed, then the presets and recent countdowns.
+pub fn render_start<B: RenderBackend>(display: &mut Display<B>, screen: &StartScreen) -> Result<(), Box<dyn std::error::Error>> {
+    display.update_size();
+    let mut frame = display.frame();
     
-    let time_string = format_duration(duration);
-    let font = get_font();
+    let focused = CellStyle { fg: Some(Color::Cyan), dim: false };
+    let hint = CellStyle { fg: None, dim: true };
+    let style_for = |focus: bool| if focus { focused } else { CellStyle::default() };
     
-    let mut char_maps = Vec::new();
-    let mut total_width = 0;
+    // Heading, blank, duration, its check, title, blank, presets, blank,
+    // recent countdowns and their blank, then the key hints
+    let recent_height = if screen.recent.is_empty() { 0 } else { screen.recent.len() + 1 };
+    let block_height = 9 + recent_height;
+    let block_width = 52;
+    let x = (display.width as usize).saturating_sub(block_width) / 2;
+    let value_x = x + 12;
+    let mut y = (display.height as usize).saturating_sub(block_height) / 2;
     
-    for ch in time_string.chars() {
-        if let Some(char_map) = font.get(&ch) {
-            char_maps.push(char_map);
-            // Use the same method as original Go: count runes in first line
-            total_width += char_map[0].chars().count();
+    draw_centered(&mut frame, y, "Start a countdown", CellStyle::default());
+    y += 2;
+    
+    for (label, value, focus) in [
+        ("Duration", &screen.duration, Focus::Duration),
+        ("Title", &screen.title, Focus::Title),
+    ] {
+        let has_focus = screen.focus == focus;
+        frame.put_str(x, y, if has_focus { "›" } else { " " }, focused);
+        frame.put_str(x + 2, y, label, style_for(has_focus));
+        frame.put_str(value_x, y, value, CellStyle::default());
+        if has_focus {
+            frame.put_str(value_x + display_width(value), y, "_", focused);
+        }
+        y += 1;
+        
+        if focus == Focus::Duration {
+            let (check, style) = match screen.validation() {
+                None => ("e.g. 25m, 1h30m or 14:15".to_string(), hint),
+                Some(Ok(duration)) => (format!("✓ {}", format_duration(duration)), CellStyle { fg: Some(Color::Green), dim: false }),
+                Some(Err(e)) => (format!("✗ {}", e), CellStyle { fg: Some(Color::Red), dim: false }),
+            };
+            frame.put_str(value_x, y, &check, style);
+            y += 1;
         }
     }
+    y += 1;
     
-    let height = char_height();
+    let presets_focus = matches!(screen.focus, Focus::Presets(_));
+    frame.put_str(x, y, if presets_focus { "›" } else { " " }, focused);
+    frame.put_str(x + 2, y, "Presets", style_for(presets_focus));
+    let mut preset_x = value_x;
+    for (index, preset) in PRESETS.iter().enumerate() {
+        frame.put_str(preset_x, y, preset, style_for(screen.focus == Focus::Presets(index)));
+        preset_x += display_width(preset) + 2;
+    }
+    y += 2;
     
-    let start_x = (display.width as usize).saturating_sub(total_width) / 2;
-    let start_y = (display.height as usize).saturating_sub(height) / 2;
+    for (index, entry) in screen.recent.iter().enumerate() {
+        let has_focus = screen.focus == Focus::Recent(index);
+        if index == 0 {
+            frame.put_str(x + 2, y, "Recent", style_for(matches!(screen.focus, Focus::Recent(_))));
+        }
+        frame.put_str(x, y, if has_focus { "›" } else { " " }, focused);
+        frame.put_str(value_x, y, &entry.duration, style_for(has_focus));
+        if let Some(title) = &entry.title {
+            frame.put_str(value_x + 10, y, title, style_for(has_focus));
+        }
+        y += 1;
+    }
+    if !screen.recent.is_empty() {
+        y += 1;
+    }
     
-    // Clear entire screen like original clear() function
-    clear_screen()?;
+    draw_centered(&mut frame, y, "↑↓ move  ←→ pick a preset  Enter start  Esc quit", hint);
+    
+    display.present(frame)
+}
+
+/// Draw the digits horizontally centred from row `top` and return the row
+/// just below them.
+fn draw_digits(frame: &mut Frame, top: usize, duration: Duration, style: DigitStyle, font: DigitFont) -> usize {
+    let start_x = (frame.width() as usize).saturating_sub(font.width(duration)) / 2;
+    
+    let digit_style = style.cell_style();
+    for (row_idx, line) in font.lines(duration).iter().enumerate() {
+        frame.put_str(start_x, top + row_idx, line, digit_style);
+    }
     
-    // Render each character symbol like original echo() function
-    let mut x = start_x;
-    for char_map in &char_maps {
-        for (row_idx, line) in char_map.iter().enumerate() {
-            let y = start_y + row_idx;
-            for (col_idx, ch) in line.chars().enumerate() {
-                execute!(stdout(), cursor::MoveTo((x + col_idx) as u16, y as u16))?;
-                execute!(stdout(), Print(ch))?;
+    top + font.height()
+}
+
+/// The title as lines ready to draw: in the text font when it fits on one
+/// line and into `room` rows along with the blank line before it, otherwise
+/// as wrapped plain text.
+fn wrapped_title(title: &Option<Title>, screen_width: u16, room: usize) -> Vec<String> {
+    let Some(title) = title else {
+        return Vec::new();
+    };
+    
+    // Leave a two column margin on either side
+    let max_width = (screen_width as usize).saturating_sub(4);
+    if text_height() < room {
+        if let Some(lines) = render_text(&title.text) {
+            if lines.iter().all(|line| display_width(line) <= max_width) {
+                return lines;
             }
         }
-        x += char_map[0].chars().count();
     }
+    wrap_title(&title.text, max_width, MAX_TITLE_LINES)
+}
+
+/// Draw title lines from row `y`, aligned within the span of the digits, or
+/// of the title itself when that is wider.
+fn draw_title(frame: &mut Frame, lines: &[String], y: usize, align: TitleAlign, digits_width: usize) {
+    let title_width = lines.iter().map(|line| display_width(line)).max().unwrap_or(0);
+    let span = digits_width.max(title_width);
+    let span_x = (frame.width() as usize).saturating_sub(span) / 2;
     
-    if let Some(title_text) = title {
-        let title_y = start_y + height + 2;
-        let title_x = (display.width as usize).saturating_sub(title_text.len()) / 2;
-        
-        execute!(stdout(), cursor::MoveTo(title_x as u16, title_y as u16))?;
-        execute!(stdout(), Print(title_text))?;
+    for (row, line) in lines.iter().enumerate() {
+        let slack = span - display_width(line);
+        let x = match align {
+            TitleAlign::Left => span_x,
+            TitleAlign::Center => span_x + slack / 2,
+            TitleAlign::Right => span_x + slack,
+        };
+        frame.put_str(x, y + row, line, CellStyle::default());
     }
-    
-    stdout().flush()?;
-    Ok(())
 }
 
-pub fn render_paused(display: &mut Display) -> Result<(), Box<dyn std::error::Error>> {
-    display.update_size();
+/// Draw the keys and what they do in a box in the middle of the screen.
+fn draw_help(frame: &mut Frame, help: &[(String, &'static str)]) {
+    let keys_width = help.iter().map(|(keys, _)| display_width(keys)).max().unwrap_or(0);
+    let lines: Vec<String> = help
+        .iter()
+        .map(|(keys, description)| format!("{}{}   {}", keys, " ".repeat(keys_width - display_width(keys)), description))
+        .collect();
+    let closing = "any key closes this";
+    let inner_width = lines.iter().map(|line| display_width(line)).chain([display_width(closing)]).max().unwrap_or(0) + 4;
     
-    // Clear screen first like original
-    clear_screen()?;
+    // Border, blank, the keys, blank, the closing hint, border
+    let box_height = lines.len() + 5;
+    let x = (frame.width() as usize).saturating_sub(inner_width + 2) / 2;
+    let top = (frame.height() as usize).saturating_sub(box_height) / 2;
     
-    let paused_text = get_paused_text();
-    let text_width = paused_text_width();
-    let text_height = paused_text_height();
+    let border = CellStyle::default();
+    let title = " Keys ";
+    let rule = "─".repeat(inner_width.saturating_sub(display_width(title) + 1));
+    frame.put_str(x, top, &format!("┌─{}{}┐", title, rule), border);
+    for row in 1..box_height - 1 {
+        frame.put_str(x, top + row, &format!("│{}│", " ".repeat(inner_width)), border);
+    }
+    frame.put_str(x, top + box_height - 1, &format!("└{}┘", "─".repeat(inner_width)), border);
     
-    let start_x = (display.width as usize).saturating_sub(text_width) / 2;
-    let start_y = (display.height as usize * 3 / 4).saturating_sub(text_height / 2);
+    for (row, line) in lines.iter().enumerate() {
+        frame.put_str(x + 3, top + 2 + row, line, CellStyle::default());
+    }
+    frame.put_str(x + 3, top + box_height - 2, closing, CellStyle { fg: None, dim: true });
+}
+
+fn draw_centered(frame: &mut Frame, y: usize, text: &str, style: CellStyle) {
+    let x = (frame.width() as usize).saturating_sub(display_width(text)) / 2;
+    frame.put_str(x, y, text, style);
+}
+
+pub fn progress_line(progress: Progress, width: usize) -> String {
+    let fraction = progress.fraction.clamp(0.0, 1.0);
+    let label = format!(" {:>3}%  ETA {}", (fraction * 100.0).floor() as u32, progress.eta.format("%H:%M:%S"));
     
-    for (row, line) in paused_text.iter().enumerate() {
-        execute!(stdout(), cursor::MoveTo(start_x as u16, (start_y + row) as u16))?;
-        execute!(stdout(), Print(line))?;
+    // Use at most two thirds of the terminal for the bar itself
+    let bar_width = (width * 2 / 3).saturating_sub(label.chars().count()).min(60);
+    if bar_width < 10 {
+        return label.trim_start().to_string();
     }
     
-    stdout().flush()?;
-    Ok(())
+    let filled = (fraction * bar_width as f64).round() as usize;
+    format!("{}{}{}", "█".repeat(filled), "░".repeat(bar_width - filled), label)
 }
 
-fn format_duration(duration: Duration) -> String {
+pub fn format_duration(duration: Duration) -> String {
     let total_seconds = duration.as_secs();
     let hours = total_seconds / 3600;
     let minutes = (total_seconds % 3600) / 60;